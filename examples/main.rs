@@ -14,17 +14,12 @@ fn main() {
                 start_hour:  6,
                 ..default()
             },
-            PGJobsPlugin::default()
+            PGJobsPlugin::default(),
+            PGJobsCommonPlugin::default()
         ))
         .register_type::<Player>()
         .insert_resource(ClearColor(Color::srgb(0.05, 0.05, 0.1)))
         .add_systems(Startup, init)
-        .add_systems(Update, (
-            wait_task_time,
-            hide_task, 
-            show_task,
-            despawn_task
-        ))
         .run();
 }
 
@@ -95,7 +90,8 @@ fn make_job() -> Job {
         JobData{
             name: "TestJob",
             tasks,
-            on_fail: JobOnFail::Cancel
+            on_fail: JobOnFail::Cancel,
+            ..default()
         }
     )
 }
@@ -0,0 +1,282 @@
+// Helpers around bevy_pg_calendar used by the scheduler and calendar based tasks
+// All access to the calendar's date and time goes through calendar_now and CalendarProbe,
+// they rely on Calendar::get_datetime, Calendar::set_datetime and Calendar: Clone.
+use bevy::ecs::resource::Resource;
+use bevy::ecs::system::{Res, ResMut};
+use bevy::reflect::Reflect;
+use bevy_pg_calendar::prelude::{Calendar, Cron};
+use chrono::{Datelike, NaiveDateTime, Timelike, TimeDelta};
+
+// How far ahead the scheduler looks for the next Cron match (one in-game year)
+const CRON_LOOKAHEAD_HOURS: i64 = 24*366;
+// Limit of in-game minutes processed in one frame, in case calendar jumps far ahead
+const MAX_MINUTES_PER_FRAME: i64 = 24*60*7;
+
+/// Current in-game date and time
+pub(crate) fn calendar_now(calendar: &Calendar) -> NaiveDateTime {
+    calendar.get_datetime()
+}
+
+/// Calendar moved to other points in time to check Cron schedules.
+/// Cron matches whole hours, so times in the current hour use the calendar itself
+/// and other times share one copy of the calendar, made on first use.
+pub(crate) struct CalendarProbe<'a> {
+    calendar: &'a Calendar,
+    hour:     NaiveDateTime,
+    probe:    Option<Calendar>
+}
+impl<'a> CalendarProbe<'a> {
+    pub(crate) fn new(calendar: &'a Calendar) -> Self {
+        CalendarProbe { calendar, hour: truncate_hour(calendar_now(calendar)), probe: None }
+    }
+    pub(crate) fn at(&mut self, dt: NaiveDateTime) -> &Calendar {
+        if truncate_hour(dt) == self.hour {
+            return self.calendar;
+        }
+        let probe = self.probe.get_or_insert_with(|| self.calendar.clone());
+        probe.set_datetime(dt);
+        return probe;
+    }
+}
+
+/// Start of the hour for given datetime
+pub(crate) fn truncate_hour(dt: NaiveDateTime) -> NaiveDateTime {
+    dt.with_minute(0).and_then(|d| d.with_second(0)).and_then(|d| d.with_nanosecond(0)).unwrap_or(dt)
+}
+
+/// Start of the minute for given datetime
+pub(crate) fn truncate_minute(dt: NaiveDateTime) -> NaiveDateTime {
+    dt.with_second(0).and_then(|d| d.with_nanosecond(0)).unwrap_or(dt)
+}
+
+/// Next full in-game hour (after the current one) at which the cron matches
+pub(crate) fn next_cron_time(cron: &Cron, calendar: &Calendar) -> Option<NaiveDateTime> {
+    let start = truncate_hour(calendar_now(calendar));
+    let mut probe = CalendarProbe::new(calendar);
+    for h in 1..=CRON_LOOKAHEAD_HOURS {
+        let dt = start + TimeDelta::hours(h);
+        if cron.is_time(probe.at(dt)){
+            return Some(dt);
+        }
+    }
+    return None;
+}
+
+/// Inclusive range of days in a year, from (month, day) to (month, day). Can wrap around new year.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct DateRange {
+    pub from: (u32, u32),
+    pub to:   (u32, u32)
+}
+impl DateRange {
+    pub fn contains(&self, dt: &NaiveDateTime) -> bool {
+        let day = (dt.month(), dt.day());
+        if self.from <= self.to {
+            self.from <= day && day <= self.to
+        } else {
+            day >= self.from || day <= self.to
+        }
+    }
+}
+
+/// Calendar schedule with minute granularity. All set conditions have to match.
+#[derive(Debug, Clone, PartialEq, Reflect, Default)]
+pub struct CalendarSchedule {
+    pub cron:       Option<Cron>,       // Hours/days from bevy_pg_calendar, every hour if None
+    pub minute:     u32,                // Minute of the hour
+    pub weekdays:   Vec<u32>,           // 0 = Monday .. 6 = Sunday, every day if empty
+    pub dates:      Option<DateRange>,  // Dates or season in the year
+    pub every_days: Option<u32>         // Every N days (counted from the start of the calendar era)
+}
+impl CalendarSchedule {
+    pub fn new(minute: u32) -> Self {
+        CalendarSchedule { minute, ..Default::default() }
+    }
+    pub fn with_cron(mut self, cron: Cron) -> Self {
+        self.cron = Some(cron);
+        self
+    }
+    pub fn with_weekdays(mut self, weekdays: Vec<u32>) -> Self {
+        self.weekdays = weekdays;
+        self
+    }
+    pub fn with_dates(mut self, from: (u32, u32), to: (u32, u32)) -> Self {
+        self.dates = Some(DateRange{from, to});
+        self
+    }
+    pub fn with_every_days(mut self, n: u32) -> Self {
+        self.every_days = Some(n);
+        self
+    }
+    pub fn parse(&mut self) {
+        if let Some(cron) = &mut self.cron {
+            cron.parse();
+        }
+    }
+    /// Checks if schedule matches the in-game minute dt
+    pub fn is_time(&self, calendar: &Calendar, dt: NaiveDateTime) -> bool {
+        self.is_time_with(&mut CalendarProbe::new(calendar), dt)
+    }
+    pub(crate) fn is_time_with(&self, probe: &mut CalendarProbe, dt: NaiveDateTime) -> bool {
+        if !self.matches_date(dt) {
+            return false;
+        }
+        if let Some(cron) = &self.cron {
+            return cron.is_time(probe.at(dt));
+        }
+        return true;
+    }
+    /// Checks minute, weekday, date and every_days conditions, Cron is not checked
+    pub fn matches_date(&self, dt: NaiveDateTime) -> bool {
+        if dt.minute() != self.minute {
+            return false;
+        }
+        if !self.weekdays.is_empty() && !self.weekdays.contains(&dt.weekday().num_days_from_monday()) {
+            return false;
+        }
+        if let Some(dates) = &self.dates {
+            if !dates.contains(&dt) {
+                return false;
+            }
+        }
+        if let Some(n) = self.every_days {
+            if n == 0 || !(dt.num_days_from_ce() as u32).is_multiple_of(n) {
+                return false;
+            }
+        }
+        return true;
+    }
+    /// Next in-game minute after the current one at which the schedule matches
+    pub fn next_time(&self, calendar: &Calendar) -> Option<NaiveDateTime> {
+        let mut probe = CalendarProbe::new(calendar);
+        next_minute_match(calendar_now(calendar), self.minute, |dt| self.is_time_with(&mut probe, dt))
+    }
+}
+
+/// First time after now, at given minute of an hour, for which is_time returns true
+fn next_minute_match(
+    now:     NaiveDateTime, 
+    minute:  u32, 
+    mut is_time: impl FnMut(NaiveDateTime) -> bool
+) -> Option<NaiveDateTime> {
+    let start = truncate_hour(now) + TimeDelta::minutes(minute as i64);
+    for h in 0..=CRON_LOOKAHEAD_HOURS {
+        let dt = start + TimeDelta::hours(h);
+        if dt > now && is_time(dt){
+            return Some(dt);
+        }
+    }
+    return None;
+}
+
+/// In-game time seen by jobs in this and the previous frame
+#[derive(Resource, Default, Debug)]
+pub struct JobCalendarTime {
+    previous: Option<NaiveDateTime>,
+    now:      Option<NaiveDateTime>
+}
+impl JobCalendarTime {
+    pub fn now(&self) -> Option<NaiveDateTime> {
+        self.now
+    }
+    /// In-game time passed since the previous frame
+    pub fn delta(&self) -> TimeDelta {
+        match (self.previous, self.now) {
+            (Some(previous), Some(now)) if now > previous => now - previous,
+            _ => TimeDelta::zero()
+        }
+    }
+    /// Starts of all in-game minutes reached since the previous frame
+    pub fn minutes(&self) -> Vec<NaiveDateTime> {
+        let mut minutes: Vec<NaiveDateTime> = Vec::new();
+        let (Some(previous), Some(now)) = (self.previous, self.now) else {return minutes};
+        let mut minute = truncate_minute(previous) + TimeDelta::minutes(1);
+        while minute <= now && (minutes.len() as i64) < MAX_MINUTES_PER_FRAME {
+            minutes.push(minute);
+            minute += TimeDelta::minutes(1);
+        }
+        return minutes;
+    }
+}
+
+pub(crate) fn update_calendar_time(
+    calendar:          Res<Calendar>,
+    mut calendar_time: ResMut<JobCalendarTime>
+){
+    let now = calendar_now(&calendar);
+    calendar_time.previous = calendar_time.now.or(Some(now));
+    calendar_time.now = Some(now);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// Test datetime shorthand, shared with other test modules
+    pub(crate) fn dt(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn date_range_contains() {
+        let summer = DateRange{from: (6, 1), to: (8, 31)};
+        assert!(summer.contains(&dt(2024, 6, 1, 0, 0)));
+        assert!(summer.contains(&dt(2024, 8, 31, 23, 59)));
+        assert!(!summer.contains(&dt(2024, 5, 31, 12, 0)));
+        assert!(!summer.contains(&dt(2024, 9, 1, 12, 0)));
+    }
+
+    #[test]
+    fn date_range_wraps_new_year() {
+        let winter = DateRange{from: (12, 1), to: (2, 28)};
+        assert!(winter.contains(&dt(2024, 12, 1, 0, 0)));
+        assert!(winter.contains(&dt(2024, 12, 31, 0, 0)));
+        assert!(winter.contains(&dt(2025, 1, 1, 0, 0)));
+        assert!(winter.contains(&dt(2025, 2, 28, 0, 0)));
+        assert!(!winter.contains(&dt(2025, 3, 1, 0, 0)));
+        assert!(!winter.contains(&dt(2024, 11, 30, 0, 0)));
+    }
+
+    #[test]
+    fn matches_date_conditions() {
+        // 2024-01-01 is a Monday
+        let schedule = CalendarSchedule::new(30).with_weekdays(vec![0, 2]);
+        assert!(schedule.matches_date(dt(2024, 1, 1, 10, 30)));
+        assert!(!schedule.matches_date(dt(2024, 1, 1, 10, 31)));
+        assert!(!schedule.matches_date(dt(2024, 1, 2, 10, 30)));
+        assert!(schedule.matches_date(dt(2024, 1, 3, 10, 30)));
+
+        let schedule = CalendarSchedule::new(0).with_dates((12, 24), (1, 6));
+        assert!(schedule.matches_date(dt(2024, 12, 25, 8, 0)));
+        assert!(!schedule.matches_date(dt(2024, 11, 25, 8, 0)));
+
+        let schedule = CalendarSchedule::new(0).with_every_days(3);
+        let matching = (1..=9).filter(|day| schedule.matches_date(dt(2024, 1, *day, 8, 0))).count();
+        assert_eq!(matching, 3);
+        assert!(!CalendarSchedule::new(0).with_every_days(0).matches_date(dt(2024, 1, 1, 8, 0)));
+    }
+
+    #[test]
+    fn next_minute_match_lookahead() {
+        let schedule = CalendarSchedule::new(30);
+        let next = |now| next_minute_match(now, schedule.minute, |dt| schedule.matches_date(dt));
+        assert_eq!(next(dt(2024, 1, 1, 10, 15)), Some(dt(2024, 1, 1, 10, 30)));
+        assert_eq!(next(dt(2024, 1, 1, 10, 30)), Some(dt(2024, 1, 1, 11, 30)));
+        assert_eq!(next(dt(2024, 1, 1, 23, 45)), Some(dt(2024, 1, 2, 0, 30)));
+
+        // Next Sunday from Monday
+        let schedule = CalendarSchedule::new(0).with_weekdays(vec![6]);
+        assert_eq!(
+            next_minute_match(dt(2024, 1, 1, 10, 0), 0, |dt| schedule.matches_date(dt)), 
+            Some(dt(2024, 1, 7, 0, 0))
+        );
+        // Across the year boundary
+        let schedule = CalendarSchedule::new(0).with_dates((1, 1), (1, 1));
+        assert_eq!(
+            next_minute_match(dt(2024, 12, 31, 22, 0), 0, |dt| schedule.matches_date(dt)), 
+            Some(dt(2025, 1, 1, 0, 0))
+        );
+        assert_eq!(next_minute_match(dt(2024, 1, 1, 0, 0), 0, |_| false), None);
+    }
+}
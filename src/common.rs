@@ -1,14 +1,54 @@
 
 // Collection of very common task implementations
 use bevy::prelude::*;
-use bevy_pg_calendar::prelude::Calendar;
+use bevy::ecs::reflect::ReflectCommandExt;
+use bevy::math::curve::{Curve, EaseFunction};
+use bevy_pg_calendar::prelude::{Calendar, CalendarNewHourEvent};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, TimeDelta};
 use rand::Rng;
 
-use crate::prelude::{PGTask, Job, JobSchedule};
+use crate::prelude::{PGTask, TaskSets, Job, JobSchedule, JobCalendarTime, JobNotPaused, JobArg, JobRng, ReflectedValue};
+use crate::calendar::CalendarProbe;
 use pg_jobs_proc_macros::PGTask;
 
 
-pub struct PGJobsCommonPlugin;
+/// Common tasks whose systems can be left out of PGJobsCommonPlugin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommonTask {
+    Despawn,
+    Hide,
+    Show,
+    Loop,
+    Teleport,
+    RandomWait,
+    Wait,               // Real time and calendar waits of WaitTask
+    WaitUntilCalendar,
+    MoveTo,
+    FollowPath,
+    Patrol,
+    Wander,
+    InsertComponent,
+    RemoveComponent,
+    Spawn,
+    DespawnTarget,
+    Tween,
+    LookAt
+}
+
+/// Registers common task types and schedules their systems in TaskSets within PGJobsSet
+#[derive(Default)]
+pub struct PGJobsCommonPlugin {
+    pub disabled: Vec<CommonTask>   // Tasks without systems added, types are still registered
+}
+impl PGJobsCommonPlugin {
+    pub fn without(mut self, task: CommonTask) -> Self {
+        self.disabled.push(task);
+        self
+    }
+    pub fn is_enabled(&self, task: CommonTask) -> bool {
+        !self.disabled.contains(&task)
+    }
+}
 
 impl Plugin for PGJobsCommonPlugin {
     fn build(&self, app: &mut App) {
@@ -20,7 +60,77 @@ impl Plugin for PGJobsCommonPlugin {
         .register_type::<WaitTask>()
         .register_type::<LoopTask>()
         .register_type::<TeleportTask>()
+        .register_type::<MoveToTask>()
+        .register_type::<FollowPathTask>()
+        .register_type::<PatrolTask>()
+        .register_type::<WanderTask>()
+        .register_type::<InsertComponentTask>()
+        .register_type::<RemoveComponentTask>()
+        .register_type::<SpawnTask>()
+        .register_type::<DespawnTargetTask>()
+        .register_type::<TweenTask>()
+        .register_type::<LookAtTask>()
+        .register_type::<WaitUntilCalendarTask>()
         ;
+
+        if self.is_enabled(CommonTask::RandomWait){
+            app.add_systems(Update, random_wait_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::Loop){
+            app.add_systems(Update, loop_task.in_set(TaskSets::Loop));
+        }
+        if self.is_enabled(CommonTask::Wait){
+            app.add_systems(Update, (
+                wait_task_time,
+                wait_idle_calendar.run_if(resource_exists::<Calendar>.and(on_message::<CalendarNewHourEvent>)),
+                wait_task_calendar.run_if(resource_exists::<Calendar>)
+            ).in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::WaitUntilCalendar){
+            app.add_systems(Update, wait_until_calendar_task.run_if(resource_exists::<Calendar>).in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::Despawn){
+            app.add_systems(Update, despawn_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::Hide){
+            app.add_systems(Update, hide_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::Show){
+            app.add_systems(Update, show_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::Teleport){
+            app.add_systems(Update, teleport_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::MoveTo){
+            app.add_systems(Update, move_to_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::FollowPath){
+            app.add_systems(Update, follow_path_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::Patrol){
+            app.add_systems(Update, patrol_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::Wander){
+            app.add_systems(Update, wander_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::InsertComponent){
+            app.add_systems(Update, insert_component_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::RemoveComponent){
+            app.add_systems(Update, remove_component_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::Spawn){
+            app.add_systems(Update, spawn_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::DespawnTarget){
+            app.add_systems(Update, despawn_target_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::Tween){
+            app.add_systems(Update, tween_task.in_set(TaskSets::Simple));
+        }
+        if self.is_enabled(CommonTask::LookAt){
+            app.add_systems(Update, look_at_task.in_set(TaskSets::Simple));
+        }
     }
 }
 
@@ -47,24 +157,482 @@ pub struct WaitTask {
     pub schedule: JobSchedule
 }
 
+/// Waits random real time between min and max seconds.
+/// The drawn delay is kept in the task, so the wait resumes after restore.
 #[derive(Component, Clone, Copy, Debug, Reflect, PGTask)]
 #[component(storage = "SparseSet")]
 #[reflect(Component)]
 pub struct RandomWaitTask{
-    min: f32,
-    max: f32
+    min:       f32,
+    max:       f32,
+    remaining: Option<f32>   // Drawn on the first run
 }
 impl RandomWaitTask {
     pub fn new(min: f32, max: f32) -> Self {
-        Self {min, max}
+        Self {min, max, remaining: None}
     }
 }
 
-#[derive(Component, Clone, Copy, Debug, Reflect, PGTask)]
+/// Absolute in-game time or the next occurrence of an hour on a given weekday
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub enum CalendarTarget {
+    At   {year: i32, month: u32, day: u32, hour: u32, minute: u32},
+    Next {hour: u32, minute: u32, weekday: Option<u32>}  // 0 = Monday .. 6 = Sunday, today or tomorrow if None
+}
+impl CalendarTarget {
+    /// Resolves target against current in-game time. Returns the deadline and whether it has already passed.
+    pub fn resolve(&self, now: NaiveDateTime) -> Option<(NaiveDateTime, bool)> {
+        match *self {
+            CalendarTarget::At{year, month, day, hour, minute} => {
+                let dt = NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, 0)?;
+                return Some((dt, dt <= now));
+            }
+            CalendarTarget::Next{hour, minute, weekday} => {
+                let today = now.date().and_hms_opt(hour, minute, 0)?;
+                let days_ahead = match weekday {
+                    Some(weekday) => (weekday as i64 - now.weekday().num_days_from_monday() as i64).rem_euclid(7),
+                    None => 0
+                };
+                let dt = today + TimeDelta::days(days_ahead);
+                if dt > now {
+                    return Some((dt, false));
+                }
+                // Passed today, next occurrence is tomorrow or in a week
+                let step = if weekday.is_some() {7} else {1};
+                return Some((dt + TimeDelta::days(step), true));
+            }
+        }
+    }
+}
+
+/// Waits until in-game time reaches the target.
+/// If the time has already passed, with complete_if_passed set the task completes immediately,
+/// otherwise At target fails the job and Next target waits for the following occurrence.
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct WaitUntilCalendarTask {
+    pub until:              CalendarTarget,
+    pub complete_if_passed: bool,
+    deadline:               Option<i64>   // Resolved target as in-game unix timestamp, kept so restore resumes the same wait
+}
+impl WaitUntilCalendarTask {
+    pub fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> Self {
+        Self {until: CalendarTarget::At{year, month, day, hour, minute}, complete_if_passed: true, deadline: None}
+    }
+    pub fn next(hour: u32, minute: u32) -> Self {
+        Self {until: CalendarTarget::Next{hour, minute, weekday: None}, complete_if_passed: true, deadline: None}
+    }
+    pub fn next_on(weekday: u32, hour: u32, minute: u32) -> Self {
+        Self {until: CalendarTarget::Next{hour, minute, weekday: Some(weekday)}, complete_if_passed: true, deadline: None}
+    }
+    pub fn with_complete_if_passed(mut self, complete_if_passed: bool) -> Self {
+        self.complete_if_passed = complete_if_passed;
+        self
+    }
+}
+
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
 #[component(storage = "SparseSet")]
 #[reflect(Component)]
 pub struct TeleportTask {
-    pub loc: Vec3
+    pub loc: JobArg<Vec3>   // Location or blackboard key holding it
+}
+impl TeleportTask {
+    pub fn new(loc: Vec3) -> Self {
+        Self {loc: JobArg::Value(loc)}
+    }
+    pub fn from_key(key: impl Into<String>) -> Self {
+        Self {loc: JobArg::key(key)}
+    }
+}
+
+/// Where the entity should move to. Positions are in world space.
+#[derive(Clone, Debug, Reflect, PartialEq)]
+pub enum MoveTarget {
+    Point(Vec3),
+    Entity(Entity),
+    Key(String)      // Blackboard or parameter key holding Vec3 or Entity
+}
+impl MoveTarget {
+    /// Current target position. None if target entity is gone or key is missing.
+    pub fn position(&self, job: &Job, targets: &Query<&GlobalTransform>) -> Option<Vec3> {
+        match self {
+            MoveTarget::Point(point) => Some(*point),
+            MoveTarget::Entity(entity) => targets.get(*entity).ok().map(|gt| gt.translation()),
+            MoveTarget::Key(key) => {
+                let value = job.read(key)?;
+                if let Some(point) = value.as_vec3() {
+                    return Some(point);
+                }
+                let entity = value.as_entity()?;
+                targets.get(entity).ok().map(|gt| gt.translation())
+            }
+        }
+    }
+}
+
+/// Moves Transform towards the target with given speed. Completes on arrival.
+/// Fails if the target is gone or max_duration (seconds) passes.
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct MoveToTask {
+    pub target:       MoveTarget,
+    pub speed:        f32,
+    pub tolerance:    f32,
+    pub max_duration: Option<f32>,
+    elapsed:          f32
+}
+impl MoveToTask {
+    pub fn new(target: MoveTarget, speed: f32, tolerance: f32) -> Self {
+        Self {target, speed, tolerance, max_duration: None, elapsed: 0.0}
+    }
+    pub fn with_max_duration(mut self, max_duration: f32) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+}
+
+/// World space point in the space of entity's Transform, which is relative to its parent if it has one
+pub fn to_parent_space(
+    child_of: Option<&ChildOf>, 
+    point:    Vec3, 
+    globals:  &Query<&GlobalTransform>
+) -> Vec3 {
+    let Some(child_of) = child_of else {return point};
+    let Ok(parent_transform) = globals.get(child_of.parent()) else {return point};
+    return parent_transform.affine().inverse().transform_point3(point);
+}
+
+/// Moves transform towards the target by at most step. Returns true if within tolerance.
+pub fn move_towards(
+    transform: &mut Transform, 
+    target:    Vec3, 
+    step:      f32, 
+    tolerance: f32
+) -> bool {
+    let diff = target - transform.translation;
+    let distance = diff.length();
+    if distance <= tolerance {
+        return true;
+    }
+    if distance <= step {
+        transform.translation = target;
+        return true;
+    }
+    transform.translation += diff / distance * step;
+    return (target - transform.translation).length() <= tolerance;
+}
+
+#[derive(Clone, Copy, Debug, Default, Reflect, PartialEq)]
+pub enum PathMode {
+    #[default]
+    Once,       // Stops at the last waypoint
+    Loop,       // Goes back to the first waypoint after the last one
+    PingPong    // Walks the path back and forth
+}
+
+/// Walks through the waypoints. Progress is kept in the task so it resumes mid-path after restore.
+/// Waypoints are in the space of the entity's Transform, relative to the parent for child entities.
+/// Completes at the end of the path for PathMode::Once or after laps for other modes.
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct FollowPathTask {
+    pub waypoints: Vec<Vec3>,
+    pub mode:      PathMode,
+    pub speed:     f32,
+    pub tolerance: f32,
+    pub laps:      Option<u32>,
+    pub index:     usize,       // Waypoint currently moving to
+    pub reverse:   bool,        // Going backwards in PingPong
+    pub lap:       u32
+}
+impl FollowPathTask {
+    pub fn new(waypoints: Vec<Vec3>, mode: PathMode, speed: f32, tolerance: f32) -> Self {
+        Self {waypoints, mode, speed, tolerance, laps: None, index: 0, reverse: false, lap: 0}
+    }
+    pub fn with_laps(mut self, laps: u32) -> Self {
+        self.laps = Some(laps);
+        self
+    }
+}
+
+/// Loops (or ping-pongs) through the waypoints waiting at each one.
+/// Waypoints are in the space of the entity's Transform, as in FollowPathTask.
+/// Runs until the job moves on, or for given number of laps.
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct PatrolTask {
+    pub waypoints: Vec<Vec3>,
+    pub ping_pong: bool,
+    pub speed:     f32,
+    pub tolerance: f32,
+    pub wait:      f32,          // Seconds to wait at each waypoint
+    pub laps:      Option<u32>,
+    pub index:     usize,        // Waypoint currently moving to
+    pub reverse:   bool,
+    pub lap:       u32,
+    pub waiting:   f32           // Seconds left to wait at the current waypoint
+}
+impl PatrolTask {
+    pub fn new(waypoints: Vec<Vec3>, speed: f32, tolerance: f32, wait: f32) -> Self {
+        Self {waypoints, ping_pong: false, speed, tolerance, wait, laps: None, index: 0, reverse: false, lap: 0, waiting: 0.0}
+    }
+    pub fn with_ping_pong(mut self) -> Self {
+        self.ping_pong = true;
+        self
+    }
+    pub fn with_laps(mut self, laps: u32) -> Self {
+        self.laps = Some(laps);
+        self
+    }
+}
+
+// Next waypoint after reaching the current one: (index, reverse, lap finished). None at the end of PathMode::Once.
+fn next_waypoint(
+    index:   usize, 
+    reverse: bool, 
+    len:     usize, 
+    mode:    PathMode
+) -> Option<(usize, bool, bool)> {
+    let last = len.saturating_sub(1);
+    match mode {
+        PathMode::Once => {
+            if index < last {Some((index+1, false, false))} else {None}
+        }
+        PathMode::Loop => {
+            if index < last {Some((index+1, false, false))} else {Some((0, false, true))}
+        }
+        PathMode::PingPong => {
+            if last == 0 {
+                Some((0, false, true))
+            } else if !reverse {
+                if index < last {Some((index+1, false, false))} else {Some((index-1, true, false))}
+            } else if index > 0 {
+                Some((index-1, true, false))
+            } else {
+                Some((1, false, true))
+            }
+        }
+    }
+}
+
+/// Area to pick random points from
+#[derive(Clone, Copy, Debug, Reflect, PartialEq)]
+pub enum WanderArea {
+    Aabb{min: Vec3, max: Vec3},
+    Circle{center: Vec3, radius: f32, normal: Vec3}   // Flat circle, normal is Vec3::Z for 2D or Vec3::Y for 3D ground
+}
+impl WanderArea {
+    pub fn random_point(&self, rng: &mut impl Rng) -> Vec3 {
+        match self {
+            WanderArea::Aabb{min, max} => {
+                let t = Vec3::new(rng.random(), rng.random(), rng.random());
+                *min + (*max - *min)*t
+            }
+            WanderArea::Circle{center, radius, normal} => {
+                let (a, b) = normal.normalize_or(Vec3::Y).any_orthonormal_pair();
+                let r = radius*rng.random::<f32>().sqrt();
+                let angle = std::f32::consts::TAU*rng.random::<f32>();
+                *center + a*r*angle.cos() + b*r*angle.sin()
+            }
+        }
+    }
+}
+
+/// Moves to random points in the area and idles between them.
+/// Area is in the space of the entity's Transform, relative to the parent for child entities.
+/// Completes after duration (seconds) or count of visited points, runs until the job moves on if neither is set.
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct WanderTask {
+    pub area:      WanderArea,
+    pub speed:     f32,
+    pub tolerance: f32,
+    pub min_wait:  f32,
+    pub max_wait:  f32,
+    pub duration:  Option<f32>,
+    pub count:     Option<u32>,
+    pub target:    Option<Vec3>,
+    pub waiting:   f32,
+    pub elapsed:   f32,
+    pub visited:   u32
+}
+impl WanderTask {
+    pub fn new(area: WanderArea, speed: f32, min_wait: f32, max_wait: f32) -> Self {
+        Self {
+            area, speed, tolerance: 0.1, min_wait, max_wait, duration: None, count: None, 
+            target: None, waiting: 0.0, elapsed: 0.0, visited: 0
+        }
+    }
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+}
+
+/// Inserts any registered reflected component into the entity.
+/// Fails the job if the type is not registered with #[reflect(Component)].
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct InsertComponentTask {
+    pub value: ReflectedValue
+}
+impl InsertComponentTask {
+    pub fn new(component: impl Reflect) -> Self {
+        Self {value: ReflectedValue::new(component)}
+    }
+}
+
+/// Removes registered component by its type path, e.g. "my_game::Customer".
+/// Fails the job if the type is not registered with #[reflect(Component)].
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct RemoveComponentTask {
+    pub type_path: String
+}
+impl RemoveComponentTask {
+    pub fn new(type_path: impl Into<String>) -> Self {
+        Self {type_path: type_path.into()}
+    }
+    pub fn of<T: TypePath>() -> Self {
+        Self {type_path: T::type_path().to_string()}
+    }
+}
+
+/// What SpawnTask creates
+#[derive(Clone, Debug, Reflect)]
+pub enum SpawnSource {
+    Scene(String),                     // Scene asset path
+    Components(Vec<ReflectedValue>)    // Registered reflected components
+}
+
+/// Spawns a scene or components and stores the new entity in the job blackboard under key.
+/// Position is world position, or local offset if spawned as a child of the job entity.
+/// Fails the job if any of the components is not registered with #[reflect(Component)].
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct SpawnTask {
+    pub source:   SpawnSource,
+    pub at:       Option<JobArg<Vec3>>,
+    pub as_child: bool,
+    pub key:      String
+}
+impl SpawnTask {
+    pub fn scene(path: impl Into<String>) -> Self {
+        Self {source: SpawnSource::Scene(path.into()), at: None, as_child: false, key: "spawned".to_string()}
+    }
+    pub fn components(components: Vec<ReflectedValue>) -> Self {
+        Self {source: SpawnSource::Components(components), at: None, as_child: false, key: "spawned".to_string()}
+    }
+    pub fn at(mut self, at: impl Into<JobArg<Vec3>>) -> Self {
+        self.at = Some(at.into());
+        self
+    }
+    pub fn as_child(mut self) -> Self {
+        self.as_child = true;
+        self
+    }
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = key.into();
+        self
+    }
+}
+
+/// Despawns entity given directly or stored in the job blackboard, e.g. by SpawnTask
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct DespawnTargetTask {
+    pub target: JobArg<Entity>
+}
+impl DespawnTargetTask {
+    pub fn new(target: Entity) -> Self {
+        Self {target: JobArg::Value(target)}
+    }
+    pub fn from_key(key: impl Into<String>) -> Self {
+        Self {target: JobArg::key(key)}
+    }
+}
+
+/// Time used by timed tasks
+#[derive(Clone, Copy, Debug, Default, Reflect, PartialEq)]
+pub enum TaskClock {
+    #[default]
+    Real,        // Real seconds
+    Calendar     // In-game seconds from bevy_pg_calendar
+}
+
+/// Interpolates Transform from its value at the start of the task to the set target fields
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct TweenTask {
+    pub translation: Option<Vec3>,
+    pub rotation:    Option<Quat>,
+    pub scale:       Option<Vec3>,
+    pub duration:    f32,
+    pub ease:        EaseFunction,
+    pub clock:       TaskClock,
+    pub elapsed:     f32,
+    pub start:       Option<Transform>   // Set on the first frame
+}
+impl TweenTask {
+    pub fn new(duration: f32, ease: EaseFunction) -> Self {
+        Self {
+            translation: None, rotation: None, scale: None, duration, ease, 
+            clock: TaskClock::Real, elapsed: 0.0, start: None
+        }
+    }
+    pub fn translation(mut self, translation: Vec3) -> Self {
+        self.translation = Some(translation);
+        self
+    }
+    pub fn rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+    pub fn scale(mut self, scale: Vec3) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+    pub fn calendar(mut self) -> Self {
+        self.clock = TaskClock::Calendar;
+        self
+    }
+}
+
+/// Rotates the entity to face the target with angular speed (radians per second).
+/// Completes when aligned within tolerance (radians), fails if the target is gone.
+/// In 3D forward (-Z) is turned to the target, with planar set local +Y is turned around Z axis (2D).
+#[derive(Component, Clone, Debug, Reflect, PGTask)]
+#[component(storage = "SparseSet")]
+#[reflect(Component)]
+pub struct LookAtTask {
+    pub target:        MoveTarget,
+    pub angular_speed: f32,
+    pub tolerance:     f32,
+    pub planar:        bool
+}
+impl LookAtTask {
+    pub fn new(target: MoveTarget, angular_speed: f32, tolerance: f32) -> Self {
+        Self {target, angular_speed, tolerance, planar: false}
+    }
+    pub fn planar(mut self) -> Self {
+        self.planar = true;
+        self
+    }
 }
 
 #[derive(Component, Clone, Copy, Debug, Reflect, PGTask)]
@@ -82,7 +650,7 @@ impl Default for LoopTask {
 
 pub fn despawn_task(
     mut commands:       Commands,
-    tasks:              Query<Entity, With<DespawnTask>>
+    tasks:              Query<Entity, (With<DespawnTask>, JobNotPaused)>
 ){
     for entity in tasks.iter(){
         commands.entity(entity).despawn();
@@ -91,7 +659,7 @@ pub fn despawn_task(
 
 pub fn loop_task(
     mut commands:   Commands,
-    mut tasks:      Query<(Entity, &LoopTask, &mut Job)>
+    mut tasks:      Query<(Entity, &LoopTask, &mut Job), JobNotPaused>
 ){
     for (task_entity, loop_task, mut job) in tasks.iter_mut(){
         if let Some(maxk) = loop_task.maxk {
@@ -108,7 +676,7 @@ pub fn loop_task(
 
 pub fn show_task(
     mut commands:   Commands,
-    mut tasks:      Query<(Entity, &mut Visibility, &mut Job), With<ShowTask>>
+    mut tasks:      Query<(Entity, &mut Visibility, &mut Job), (With<ShowTask>, JobNotPaused)>
 ){
     for (task_entity, mut vis, mut job) in tasks.iter_mut(){
         *vis = Visibility::Inherited;
@@ -118,7 +686,7 @@ pub fn show_task(
 
 pub fn hide_task(
     mut commands:   Commands,
-    mut tasks:      Query<(Entity, &mut Visibility, &mut Job), With<HideTask>>
+    mut tasks:      Query<(Entity, &mut Visibility, &mut Job), (With<HideTask>, JobNotPaused)>
 ){
     for (task_entity, mut vis, mut job) in tasks.iter_mut(){
         *vis = Visibility::Hidden;
@@ -128,30 +696,41 @@ pub fn hide_task(
 
 pub fn teleport_task(
     mut commands:   Commands,
-    mut tasks:      Query<(Entity, &mut Transform, &TeleportTask, &mut Job)>
+    mut tasks:      Query<(Entity, &mut Transform, &TeleportTask, &mut Job), JobNotPaused>
 ){
     for (task_entity, mut transform, teleport_task, mut job) in tasks.iter_mut(){
-        transform.translation = teleport_task.loc;
+        let Some(loc) = teleport_task.loc.resolve(&job) else {
+            job.fail_with(&mut commands, &task_entity, "TeleportTask location missing");
+            continue;
+        };
+        transform.translation = loc;
         job.next_task(&mut commands, &task_entity);
     }  
 }
 
 pub fn random_wait_task(
     mut commands:      Commands,
-    mut tasks:         Query<(Entity, &RandomWaitTask)>,
+    time:              Res<Time>,
+    mut job_rng:       ResMut<JobRng>,
+    mut tasks:         Query<(Entity, &mut RandomWaitTask, &mut Job), JobNotPaused>,
 ){
-    for (task_entity, random_wait_task) in tasks.iter_mut(){
-        let mut rng = rand::rng();
-        let random_delay: f32 = rng.random_range(random_wait_task.min..=random_wait_task.max);
-        commands.entity(task_entity).insert(WaitTask{schedule: JobSchedule::RealDelay(random_delay)});
-        commands.entity(task_entity).remove::<RandomWaitTask>();
+    for (task_entity, mut random_wait_task, mut job) in tasks.iter_mut(){
+        let remaining = match random_wait_task.remaining {
+            Some(remaining) => remaining - time.delta_secs(),
+            None => job_rng.entity(task_entity).random_range(random_wait_task.min..=random_wait_task.max)
+        };
+        if remaining > 0.0 {
+            random_wait_task.remaining = Some(remaining);
+        } else {
+            job.next_task(&mut commands, &task_entity);
+        }
     }
 }
 
 pub fn wait_task_time(
     mut commands:   Commands,
     time:           Res<Time>,
-    mut tasks:      Query<(Entity, &mut WaitTask, &mut Job)>,
+    mut tasks:      Query<(Entity, &mut WaitTask, &mut Job), JobNotPaused>,
 ){
     for (task_entity, mut wait_task, mut job) in tasks.iter_mut(){
         match &mut wait_task.schedule {
@@ -159,8 +738,8 @@ pub fn wait_task_time(
                 if *delay > 0.0 {
                     *delay -= time.delta_secs();
                 } else {
-                    job.next_task(&mut commands, &task_entity);
                     commands.entity(task_entity).remove::<WaitTask>();
+                    job.next_task(&mut commands, &task_entity);
                 }
             }
             _ => {}
@@ -172,14 +751,14 @@ pub fn wait_task_time(
 pub fn wait_idle_calendar(
     mut commands: Commands,
     calendar:     Res<Calendar>,
-    mut tasks:    Query<(Entity, &mut WaitTask, &mut Job)>
+    mut tasks:    Query<(Entity, &mut WaitTask, &mut Job), JobNotPaused>
 ){
     for (task_entity, mut wait_task, mut job) in tasks.iter_mut(){
         match &mut wait_task.schedule {
                 JobSchedule::Cron(cron) => {
                     if cron.is_time(&calendar){
-                        job.next_task(&mut commands, &task_entity);
                         commands.entity(task_entity).remove::<WaitTask>();
+                        job.next_task(&mut commands, &task_entity);
                     }
                  }
                  JobSchedule::Delay(delay) => {
@@ -194,3 +773,423 @@ pub fn wait_idle_calendar(
         }
     }
 }
+
+
+// Handles waits with in-game minute granularity, runs every frame
+pub fn wait_task_calendar(
+    mut commands:  Commands,
+    calendar:      Res<Calendar>,
+    calendar_time: Res<JobCalendarTime>,
+    mut tasks:     Query<(Entity, &mut WaitTask, &mut Job), JobNotPaused>
+){
+    let minutes = calendar_time.minutes();
+    if minutes.is_empty(){
+        return;
+    }
+    let mut probe = CalendarProbe::new(&calendar);
+    for (task_entity, mut wait_task, mut job) in tasks.iter_mut(){
+        match &mut wait_task.schedule {
+            JobSchedule::DelayMinutes(delay) => {
+                *delay = delay.saturating_sub(minutes.len() as u32);
+                if *delay == 0 {
+                    commands.entity(task_entity).remove::<WaitTask>();
+                    job.next_task(&mut commands, &task_entity);
+                }
+            }
+            JobSchedule::Calendar(schedule) => {
+                if minutes.iter().any(|minute| schedule.is_time_with(&mut probe, *minute)){
+                    commands.entity(task_entity).remove::<WaitTask>();
+                    job.next_task(&mut commands, &task_entity);
+                }
+            }
+            _=> {}
+        }
+    }
+}
+
+pub fn move_to_task(
+    mut commands:   Commands,
+    time:           Res<Time>,
+    targets:        Query<&GlobalTransform>,
+    mut tasks:      Query<(Entity, &mut Transform, Option<&ChildOf>, &mut MoveToTask, &mut Job), JobNotPaused>
+){
+    for (task_entity, mut transform, child_of, mut move_task, mut job) in tasks.iter_mut(){
+        let Some(target) = move_task.target.position(&job, &targets) else {
+            job.fail_with(&mut commands, &task_entity, "MoveToTask target missing");
+            continue;
+        };
+        let target = to_parent_space(child_of, target, &targets);
+        move_task.elapsed += time.delta_secs();
+        if move_towards(&mut transform, target, move_task.speed*time.delta_secs(), move_task.tolerance){
+            job.next_task(&mut commands, &task_entity);
+        } else if move_task.max_duration.is_some_and(|max| move_task.elapsed >= max){
+            job.fail_with(&mut commands, &task_entity, "MoveToTask timed out");
+        }
+    }
+}
+
+pub fn follow_path_task(
+    mut commands:   Commands,
+    time:           Res<Time>,
+    mut tasks:      Query<(Entity, &mut Transform, &mut FollowPathTask, &mut Job), JobNotPaused>
+){
+    for (task_entity, mut transform, mut path, mut job) in tasks.iter_mut(){
+        let Some(waypoint) = path.waypoints.get(path.index).copied() else {
+            job.next_task(&mut commands, &task_entity);
+            continue;
+        };
+        if !move_towards(&mut transform, waypoint, path.speed*time.delta_secs(), path.tolerance){
+            continue;
+        }
+        match next_waypoint(path.index, path.reverse, path.waypoints.len(), path.mode) {
+            Some((index, reverse, lap_done)) => {
+                path.index = index;
+                path.reverse = reverse;
+                if lap_done {
+                    path.lap += 1;
+                    if path.laps.is_some_and(|laps| path.lap >= laps){
+                        job.next_task(&mut commands, &task_entity);
+                    }
+                }
+            }
+            None => {
+                job.next_task(&mut commands, &task_entity);
+            }
+        }
+    }
+}
+
+pub fn patrol_task(
+    mut commands:   Commands,
+    time:           Res<Time>,
+    mut tasks:      Query<(Entity, &mut Transform, &mut PatrolTask, &mut Job), JobNotPaused>
+){
+    for (task_entity, mut transform, mut patrol, mut job) in tasks.iter_mut(){
+        if patrol.waiting > 0.0 {
+            patrol.waiting -= time.delta_secs();
+            continue;
+        }
+        let Some(waypoint) = patrol.waypoints.get(patrol.index).copied() else {
+            job.next_task(&mut commands, &task_entity);
+            continue;
+        };
+        if !move_towards(&mut transform, waypoint, patrol.speed*time.delta_secs(), patrol.tolerance){
+            continue;
+        }
+        let mode = if patrol.ping_pong {PathMode::PingPong} else {PathMode::Loop};
+        if let Some((index, reverse, lap_done)) = next_waypoint(patrol.index, patrol.reverse, patrol.waypoints.len(), mode){
+            patrol.index = index;
+            patrol.reverse = reverse;
+            patrol.waiting = patrol.wait;
+            if lap_done {
+                patrol.lap += 1;
+                if patrol.laps.is_some_and(|laps| patrol.lap >= laps){
+                    job.next_task(&mut commands, &task_entity);
+                }
+            }
+        }
+    }
+}
+
+pub fn wander_task(
+    mut commands:   Commands,
+    time:           Res<Time>,
+    mut job_rng:    ResMut<JobRng>,
+    mut tasks:      Query<(Entity, &mut Transform, &mut WanderTask, &mut Job), JobNotPaused>
+){
+    for (task_entity, mut transform, mut wander, mut job) in tasks.iter_mut(){
+        wander.elapsed += time.delta_secs();
+        if wander.duration.is_some_and(|duration| wander.elapsed >= duration){
+            job.next_task(&mut commands, &task_entity);
+            continue;
+        }
+        if wander.waiting > 0.0 {
+            wander.waiting -= time.delta_secs();
+            continue;
+        }
+        let target = match wander.target {
+            Some(target) => target,
+            None => {
+                let target = wander.area.random_point(job_rng.entity(task_entity));
+                wander.target = Some(target);
+                target
+            }
+        };
+        if !move_towards(&mut transform, target, wander.speed*time.delta_secs(), wander.tolerance){
+            continue;
+        }
+        wander.target = None;
+        wander.visited += 1;
+        if wander.count.is_some_and(|count| wander.visited >= count){
+            job.next_task(&mut commands, &task_entity);
+            continue;
+        }
+        wander.waiting = if wander.max_wait > wander.min_wait {
+            job_rng.entity(task_entity).random_range(wander.min_wait..=wander.max_wait)
+        } else {
+            wander.min_wait
+        };
+    }
+}
+
+pub fn insert_component_task(
+    mut commands:   Commands,
+    registry:       Res<AppTypeRegistry>,
+    mut tasks:      Query<(Entity, &InsertComponentTask, &mut Job), JobNotPaused>
+){
+    let registry = registry.read();
+    for (task_entity, insert_task, mut job) in tasks.iter_mut(){
+        if !insert_task.value.is_component(&registry){
+            let type_path = insert_task.value.type_path().unwrap_or("unknown");
+            job.fail_with(&mut commands, &task_entity, format!("InsertComponentTask type {} is not a registered component", type_path));
+            continue;
+        }
+        commands.entity(task_entity).insert_reflect(insert_task.value.to_boxed());
+        job.next_task(&mut commands, &task_entity);
+    }
+}
+
+pub fn remove_component_task(
+    mut commands:   Commands,
+    registry:       Res<AppTypeRegistry>,
+    mut tasks:      Query<(Entity, &RemoveComponentTask, &mut Job), JobNotPaused>
+){
+    let registry = registry.read();
+    for (task_entity, remove_task, mut job) in tasks.iter_mut(){
+        let registered = registry.get_with_type_path(&remove_task.type_path)
+            .is_some_and(|registration| registration.data::<ReflectComponent>().is_some());
+        if !registered {
+            job.fail_with(&mut commands, &task_entity, format!("RemoveComponentTask type {} is not a registered component", remove_task.type_path));
+            continue;
+        }
+        commands.entity(task_entity).remove_reflect(remove_task.type_path.clone());
+        job.next_task(&mut commands, &task_entity);
+    }
+}
+
+pub fn spawn_task(
+    mut commands:   Commands,
+    ass:            Res<AssetServer>,
+    registry:       Res<AppTypeRegistry>,
+    mut tasks:      Query<(Entity, &SpawnTask, &mut Job), JobNotPaused>
+){
+    let registry = registry.read();
+    for (task_entity, spawn_task, mut job) in tasks.iter_mut(){
+        if let SpawnSource::Components(components) = &spawn_task.source 
+            && let Some(component) = components.iter().find(|component| !component.is_component(&registry)) {
+            let type_path = component.type_path().unwrap_or("unknown");
+            job.fail_with(&mut commands, &task_entity, format!("SpawnTask type {} is not a registered component", type_path));
+            continue;
+        }
+        let at = match &spawn_task.at {
+            Some(at) => {
+                let Some(at) = at.resolve(&job) else {
+                    job.fail_with(&mut commands, &task_entity, "SpawnTask position missing");
+                    continue;
+                };
+                Some(at)
+            }
+            None => None
+        };
+        let spawned = commands.spawn_empty().id();
+        match &spawn_task.source {
+            SpawnSource::Scene(path) => {
+                commands.entity(spawned).insert(SceneRoot(ass.load(path.clone())));
+            }
+            SpawnSource::Components(components) => {
+                for component in components.iter(){
+                    commands.entity(spawned).insert_reflect(component.to_boxed());
+                }
+            }
+        }
+        if let Some(at) = at {
+            commands.entity(spawned).insert(Transform::from_translation(at));
+        }
+        if spawn_task.as_child {
+            commands.entity(task_entity).add_child(spawned);
+        }
+        job.write(spawn_task.key.clone(), spawned);
+        job.next_task(&mut commands, &task_entity);
+    }
+}
+
+pub fn despawn_target_task(
+    mut commands:   Commands,
+    mut tasks:      Query<(Entity, &DespawnTargetTask, &mut Job), JobNotPaused>
+){
+    for (task_entity, despawn_task, mut job) in tasks.iter_mut(){
+        let Some(target) = despawn_task.target.resolve(&job) else {
+            job.fail_with(&mut commands, &task_entity, "DespawnTargetTask target missing");
+            continue;
+        };
+        commands.entity(target).try_despawn();
+        job.next_task(&mut commands, &task_entity);
+    }
+}
+
+pub fn tween_task(
+    mut commands:   Commands,
+    time:           Res<Time>,
+    calendar_time:  Res<JobCalendarTime>,
+    mut tasks:      Query<(Entity, &mut Transform, &mut TweenTask, &mut Job), JobNotPaused>
+){
+    let calendar_delta = calendar_time.delta().num_milliseconds() as f32 / 1000.0;
+    for (task_entity, mut transform, mut tween, mut job) in tasks.iter_mut(){
+        let start = *tween.start.get_or_insert(*transform);
+        tween.elapsed += match tween.clock {
+            TaskClock::Real => time.delta_secs(),
+            TaskClock::Calendar => calendar_delta
+        };
+        let t = if tween.duration > 0.0 {(tween.elapsed / tween.duration).min(1.0)} else {1.0};
+        let k = tween.ease.sample_clamped(t);
+        if let Some(translation) = tween.translation {
+            transform.translation = start.translation.lerp(translation, k);
+        }
+        if let Some(rotation) = tween.rotation {
+            transform.rotation = start.rotation.slerp(rotation, k);
+        }
+        if let Some(scale) = tween.scale {
+            transform.scale = start.scale.lerp(scale, k);
+        }
+        if t >= 1.0 {
+            job.next_task(&mut commands, &task_entity);
+        }
+    }
+}
+
+pub fn look_at_task(
+    mut commands:   Commands,
+    time:           Res<Time>,
+    targets:        Query<&GlobalTransform>,
+    mut tasks:      Query<(Entity, &mut Transform, Option<&ChildOf>, &LookAtTask, &mut Job), JobNotPaused>
+){
+    for (task_entity, mut transform, child_of, look_task, mut job) in tasks.iter_mut(){
+        let Some(target) = look_task.target.position(&job, &targets) else {
+            job.fail_with(&mut commands, &task_entity, "LookAtTask target missing");
+            continue;
+        };
+        let target = to_parent_space(child_of, target, &targets);
+        let direction = target - transform.translation;
+        let desired = if look_task.planar {
+            if direction.truncate().length_squared() < f32::EPSILON {
+                transform.rotation
+            } else {
+                Quat::from_rotation_z(direction.y.atan2(direction.x) - std::f32::consts::FRAC_PI_2)
+            }
+        } else if direction.length_squared() < f32::EPSILON {
+            transform.rotation
+        } else {
+            transform.looking_at(target, Vec3::Y).rotation
+        };
+        transform.rotation = transform.rotation.rotate_towards(desired, look_task.angular_speed*time.delta_secs());
+        if transform.rotation.angle_between(desired) <= look_task.tolerance {
+            job.next_task(&mut commands, &task_entity);
+        }
+    }
+}
+
+pub fn wait_until_calendar_task(
+    mut commands:  Commands,
+    calendar_time: Res<JobCalendarTime>,
+    mut tasks:     Query<(Entity, &mut WaitUntilCalendarTask, &mut Job), JobNotPaused>
+){
+    let Some(now) = calendar_time.now() else {return};
+    for (task_entity, mut wait_task, mut job) in tasks.iter_mut(){
+        if wait_task.deadline.is_none(){
+            let Some((deadline, passed)) = wait_task.until.resolve(now) else {
+                job.fail_with(&mut commands, &task_entity, "WaitUntilCalendarTask invalid date");
+                continue;
+            };
+            if passed {
+                if wait_task.complete_if_passed {
+                    job.next_task(&mut commands, &task_entity);
+                    continue;
+                }
+                if let CalendarTarget::At{..} = wait_task.until {
+                    job.fail_with(&mut commands, &task_entity, "WaitUntilCalendarTask time already passed");
+                    continue;
+                }
+            }
+            wait_task.deadline = Some(deadline.and_utc().timestamp());
+        }
+        if wait_task.deadline.is_some_and(|deadline| now.and_utc().timestamp() >= deadline){
+            job.next_task(&mut commands, &task_entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::tests::dt;
+
+    // Walks the path from index 0 and collects visited indexes with lap flags
+    fn walk(len: usize, mode: PathMode, steps: usize) -> Vec<(usize, bool)> {
+        let mut visited = Vec::new();
+        let (mut index, mut reverse) = (0, false);
+        for _ in 0..steps {
+            let Some((next, next_reverse, lap)) = next_waypoint(index, reverse, len, mode) else {break};
+            visited.push((next, lap));
+            index = next;
+            reverse = next_reverse;
+        }
+        return visited;
+    }
+
+    #[test]
+    fn next_waypoint_once() {
+        assert_eq!(walk(3, PathMode::Once, 10), vec![(1, false), (2, false)]);
+        assert_eq!(walk(1, PathMode::Once, 10), vec![]);
+    }
+
+    #[test]
+    fn next_waypoint_loop() {
+        assert_eq!(walk(3, PathMode::Loop, 4), vec![(1, false), (2, false), (0, true), (1, false)]);
+        assert_eq!(walk(1, PathMode::Loop, 2), vec![(0, true), (0, true)]);
+    }
+
+    #[test]
+    fn next_waypoint_ping_pong() {
+        assert_eq!(
+            walk(3, PathMode::PingPong, 6), 
+            vec![(1, false), (2, false), (1, false), (0, false), (1, true), (2, false)]
+        );
+        assert_eq!(walk(2, PathMode::PingPong, 3), vec![(1, false), (0, false), (1, true)]);
+        // Single waypoint stays in place, every step is a lap
+        assert_eq!(walk(1, PathMode::PingPong, 2), vec![(0, true), (0, true)]);
+    }
+
+    #[test]
+    fn calendar_target_at() {
+        let target = CalendarTarget::At{year: 2024, month: 3, day: 10, hour: 9, minute: 0};
+        assert_eq!(target.resolve(dt(2024, 3, 10, 8, 0)), Some((dt(2024, 3, 10, 9, 0), false)));
+        assert_eq!(target.resolve(dt(2024, 3, 10, 9, 0)), Some((dt(2024, 3, 10, 9, 0), true)));
+        assert_eq!(target.resolve(dt(2025, 1, 1, 0, 0)), Some((dt(2024, 3, 10, 9, 0), true)));
+        let invalid = CalendarTarget::At{year: 2024, month: 2, day: 30, hour: 9, minute: 0};
+        assert_eq!(invalid.resolve(dt(2024, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn calendar_target_next_hour() {
+        let target = CalendarTarget::Next{hour: 9, minute: 30, weekday: None};
+        assert_eq!(target.resolve(dt(2024, 1, 1, 8, 0)), Some((dt(2024, 1, 1, 9, 30), false)));
+        // Passed today, next one is tomorrow, also across the year boundary
+        assert_eq!(target.resolve(dt(2024, 1, 1, 10, 0)), Some((dt(2024, 1, 2, 9, 30), true)));
+        assert_eq!(target.resolve(dt(2024, 12, 31, 9, 30)), Some((dt(2025, 1, 1, 9, 30), true)));
+    }
+
+    #[test]
+    fn calendar_target_next_weekday() {
+        // 2024-01-03 is a Wednesday
+        let friday = CalendarTarget::Next{hour: 9, minute: 0, weekday: Some(4)};
+        assert_eq!(friday.resolve(dt(2024, 1, 3, 12, 0)), Some((dt(2024, 1, 5, 9, 0), false)));
+        let monday = CalendarTarget::Next{hour: 9, minute: 0, weekday: Some(0)};
+        assert_eq!(monday.resolve(dt(2024, 1, 3, 12, 0)), Some((dt(2024, 1, 8, 9, 0), false)));
+        // Today, not passed yet
+        let wednesday = CalendarTarget::Next{hour: 18, minute: 0, weekday: Some(2)};
+        assert_eq!(wednesday.resolve(dt(2024, 1, 3, 12, 0)), Some((dt(2024, 1, 3, 18, 0), false)));
+        // Today, already passed, next one is in a week
+        let wednesday = CalendarTarget::Next{hour: 9, minute: 0, weekday: Some(2)};
+        assert_eq!(wednesday.resolve(dt(2024, 1, 3, 12, 0)), Some((dt(2024, 1, 10, 9, 0), true)));
+    }
+}
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy::app::{App, Plugin, PreUpdate, Update, Startup};
+use bevy::app::{App, Plugin, PreUpdate, Update, PostUpdate, Startup};
 use bevy::asset::{Asset, AssetServer, Assets, LoadedFolder, Handle};
 use bevy::ecs::schedule::common_conditions::on_message;
 use bevy::ecs::schedule::IntoScheduleConfigs;
@@ -7,15 +7,21 @@ use bevy::ecs::entity::Entity;
 use bevy::ecs::message::{Message, MessageReader};
 use bevy::ecs::reflect::ReflectComponent;
 use bevy::ecs::component::Component;
+use bevy::ecs::query::{QueryFilter, Without};
 use bevy::ecs::system::{Commands, Local, Res, ResMut};
 use bevy::ecs::resource::Resource;
 use bevy::reflect::{Reflect, TypePath};
 // use bevy_common_assets::json::JsonAssetPlugin;
 // use bevy_common_assets::toml::TomlAssetPlugin;
 use bevy_pg_calendar::prelude::{Calendar, CalendarNewHourEvent, Cron};
+use bevy::platform::collections::HashMap;
+use chrono::NaiveDateTime;
+use std::collections::VecDeque;
 use std::hash::Hash;
 
-use super::types::{PGTask, JobData, Job};
+use super::calendar::{calendar_now, next_cron_time, update_calendar_time, CalendarProbe, CalendarSchedule, JobCalendarTime};
+use super::rng::{chance, prune_job_rng, weighted, JobRng};
+use super::types::{PGTask, JobData, Job, JobParams, JobValue, JobLifetime, JobExpireAction};
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum TaskSets {
@@ -32,13 +38,15 @@ pub struct PGJobsSet;
 
 pub struct PGJobsPlugin {
     pub active: bool,
-    pub debug:  bool
+    pub debug:  bool,
+    pub seed:   Option<u64>   // Seed for JobRng, random if not provided
 }
 impl Default for PGJobsPlugin {
     fn default() -> Self {
         PGJobsPlugin{
             active:        true,
-            debug:         true
+            debug:         true,
+            seed:          None
         }
     }
 }
@@ -51,6 +59,17 @@ impl Plugin for PGJobsPlugin {
 
         .add_message::<StopJobEvent>()
         .add_message::<StartJobEvent>()
+        .add_message::<JobStarted>()
+        .add_message::<JobTaskChanged>()
+        .add_message::<JobCompleted>()
+        .add_message::<JobFailed>()
+        .add_message::<JobCancelled>()
+        .add_message::<JobEnded>()
+        .add_message::<PauseJobsEvent>()
+        .add_message::<FailJobEvent>()
+        .add_message::<EnqueueJobEvent>()
+        .register_type::<JobQueue>()
+        .add_message::<ResumeJobsEvent>()
 
         .configure_sets(Update, PGJobsSet.run_if(if_jobs_active))
         .configure_sets(
@@ -73,20 +92,38 @@ impl Plugin for PGJobsPlugin {
         .insert_resource(JobSettings::init(self.active, self.debug))
         .insert_resource(JobCatalog::init())
         .insert_resource(JobScheduler::init())
+        .init_resource::<JobCalendarTime>()
+        .insert_resource(match self.seed {
+            Some(seed) => JobRng::new(seed),
+            None => JobRng::from_entropy()
+        })
 
         .add_systems(Startup,   init)
         .add_systems(Update,    track.run_if(resource_exists::<LoadedJobDataHandles>
                                      .and(resource_exists::<LoadedJobTriggerHandles>)))
 
         .add_systems(PreUpdate, (
-                trigger_jobs_calendar.run_if(on_message::<CalendarNewHourEvent>), 
-                trigger_jobs_time
-            ).chain().run_if(if_jobs_active)
+                // Calendar time is tracked even with jobs deactivated, so reactivation doesn't replay the gap
+                update_calendar_time.run_if(resource_exists::<Calendar>),
+                (
+                    trigger_jobs_calendar.run_if(on_message::<CalendarNewHourEvent>), 
+                    trigger_jobs_calendar_minutes.run_if(resource_exists::<Calendar>),
+                    trigger_jobs_time,
+                    trigger_jobs_after.run_if(on_message::<JobEnded>),
+                    expire_jobs
+                ).chain().run_if(if_jobs_active)
+            ).chain()
         )
 
+        .add_systems(PostUpdate, (stamp_finished_jobs, prune_job_rng))
+
         .add_systems(PreUpdate, (
+                fail_job.run_if(on_message::<FailJobEvent>),
                 stop_job.run_if(on_message::<StopJobEvent>), 
-                start_job.run_if(on_message::<StartJobEvent>)
+                start_job.run_if(on_message::<StartJobEvent>),
+                enqueue_job.run_if(on_message::<EnqueueJobEvent>),
+                advance_job_queues,
+                pause_jobs.run_if(on_message::<PauseJobsEvent>.or(on_message::<ResumeJobsEvent>))
             ).chain()
         );
 
@@ -128,15 +165,145 @@ fn observe_remove_job(
 
 
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StopMode {
+    #[default]
+    Cancel,    // Go through Job::cancel, running cleanup tasks first
+    Remove     // Remove the Job right away skipping cleanup tasks, current task component is removed with it
+}
+
 #[derive(Message)]
 pub struct StopJobEvent {
-    pub entity:     Entity
+    pub entity:     Entity,
+    pub keep_queue: bool,    // Start next job from JobQueue instead of clearing it
+    pub mode:       StopMode
+}
+impl StopJobEvent {
+    pub fn new(entity: Entity) -> Self {
+        StopJobEvent { entity, keep_queue: false, mode: StopMode::Cancel }
+    }
+    pub fn keep_queue(mut self) -> Self {
+        self.keep_queue = true;
+        self
+    }
+    pub fn cancel(mut self) -> Self {
+        self.mode = StopMode::Cancel;
+        self
+    }
+    /// Removes the job without running its cleanup tasks
+    pub fn remove(mut self) -> Self {
+        self.mode = StopMode::Remove;
+        self
+    }
+}
+
+/// Fails the job on the entity, applying its JobOnFail strategy
+#[derive(Message)]
+pub struct FailJobEvent {
+    pub entity:     Entity,
+    pub reason:     String
+}
+
+/// Adds job to the end of entity's JobQueue. Starts right away if entity has no job.
+#[derive(Message)]
+pub struct EnqueueJobEvent {
+    pub name:   &'static str,
+    pub entity: Entity,
+    pub params: JobParams
+}
+impl EnqueueJobEvent {
+    pub fn new(name: &'static str, entity: Entity) -> Self {
+        EnqueueJobEvent { name, entity, params: JobParams::default() }
+    }
+    pub fn with_param(mut self, key: impl Into<String>, value: impl Into<JobValue>) -> Self {
+        self.params.set(key, value);
+        self
+    }
 }
 
 #[derive(Message)]
 pub struct StartJobEvent {
     pub name: &'static str,
-    pub entity: Entity
+    pub entity: Entity,
+    pub params: JobParams
+}
+impl StartJobEvent {
+    pub fn new(name: &'static str, entity: Entity) -> Self {
+        StartJobEvent { name, entity, params: JobParams::default() }
+    }
+    pub fn with_param(mut self, key: impl Into<String>, value: impl Into<JobValue>) -> Self {
+        self.params.set(key, value);
+        self
+    }
+    pub fn with_params(mut self, params: JobParams) -> Self {
+        self.params = params;
+        self
+    }
+    /// Job on the parent entity gets failures escalated by JobOnFail::Escalate
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.params = self.params.with_parent(parent);
+        self
+    }
+}
+
+/// Sent when job was assigned to an entity or started on a new one
+#[derive(Message, Debug, Clone)]
+pub struct JobStarted {
+    pub entity:     Entity,
+    pub name:       &'static str,
+    pub task_id:    u32
+}
+
+/// Sent when job moved from one task to another
+#[derive(Message, Debug, Clone)]
+pub struct JobTaskChanged {
+    pub entity:     Entity,
+    pub name:       &'static str,
+    pub from:       u32,
+    pub to:         u32
+}
+
+/// Sent when job finished its last task
+#[derive(Message, Debug, Clone)]
+pub struct JobCompleted {
+    pub entity:     Entity,
+    pub name:       &'static str,
+    pub task_id:    u32
+}
+
+/// Sent when job failed, before its JobOnFail strategy is applied
+#[derive(Message, Debug, Clone)]
+pub struct JobFailed {
+    pub entity:     Entity,
+    pub name:       &'static str,
+    pub task_id:    u32,
+    pub reason:     String
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum JobCancelReason {
+    Requested,   // Job::cancel called by user code
+    Failed,      // JobOnFail strategy cancelled the job
+    Stopped,     // StopJobEvent
+    Expired      // JobData::expiry lifetime ran out
+}
+
+/// Sent when job was cancelled before finishing its tasks
+#[derive(Message, Debug, Clone)]
+pub struct JobCancelled {
+    pub entity:     Entity,
+    pub name:       &'static str,
+    pub task_id:    u32,
+    pub reason:     JobCancelReason
+}
+
+/// Sent once when job is over for good: completed, or cancelled or failed after its cleanup tasks.
+/// Not sent for jobs recovered by JobOnFail or removed with StopMode::Remove.
+#[derive(Message, Debug, Clone)]
+pub struct JobEnded {
+    pub entity:     Entity,
+    pub name:       &'static str,
+    pub outcome:    JobOutcome   // Completed, Failed or Cancelled
 }
 
 #[derive(Resource)]
@@ -203,12 +370,16 @@ fn track(
     if job_ready.data_ready && job_ready.triggers_ready {
 
         for (_job_id, jobdata) in ass_jobdata.iter_mut(){
-            jobs_catalog.add(jobdata.clone());
+            if let Err(err) = jobs_catalog.insert(jobdata.clone()){
+                error!(" [JOBS] {}", err);
+            }
         }
 
         for (_job_id, jobtrigger) in ass_jobtrigger.iter_mut(){
             jobtrigger.schedule.parse();
-            jobs_scheduler.add(jobtrigger.clone());
+            if let Err(err) = jobs_scheduler.insert(jobtrigger.clone()){
+                error!(" [JOBS] {}", err);
+            }
         }
 
         for (_job_id, trigger_data) in ass_triggers.iter_mut(){
@@ -216,7 +387,9 @@ fn track(
                 jobtrigger.schedule.parse();
                 info!(" [JOBS] Added JobTrigger {} active: {}", 
                      jobtrigger.trigger_id, jobtrigger.active);
-                jobs_scheduler.add(jobtrigger.clone());
+                if let Err(err) = jobs_scheduler.insert(jobtrigger.clone()){
+                    error!(" [JOBS] {}", err);
+                }
             }
         }
 
@@ -231,29 +404,78 @@ pub fn if_jobs_active(
     job_settings.active
 }
 
-// Stores JobDatas from assets job.toml files
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobsError {
+    DuplicateJob(&'static str),
+    MissingJob(String),
+    DuplicateTrigger(u32),
+    MissingTrigger(u32)
+}
+impl std::fmt::Display for JobsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobsError::DuplicateJob(name) => write!(f, "Job {} already in the catalog", name),
+            JobsError::MissingJob(name) => write!(f, "Missing job in the catalog: {}", name),
+            JobsError::DuplicateTrigger(trigger_id) => write!(f, "Job trigger {} already in the scheduler", trigger_id),
+            JobsError::MissingTrigger(trigger_id) => write!(f, "Missing job trigger id in the scheduler: {}", trigger_id)
+        }
+    }
+}
+impl std::error::Error for JobsError {}
+
+// Stores JobDatas from assets job.toml files. Keyed by job name, iterated in insertion order.
 #[derive(Resource)]
 pub struct JobCatalog {
-    pub data: Vec<JobData>
+    data:  HashMap<&'static str, JobData>,
+    order: Vec<&'static str>
 }
 impl JobCatalog {
     pub fn init() -> Self {
-        JobCatalog { data: Vec::new() }
+        JobCatalog { data: HashMap::default(), order: Vec::new() }
+    }
+    pub fn insert(&mut self, mut jobdata: JobData) -> Result<(), JobsError> {
+        if self.data.contains_key(jobdata.name) {
+            return Err(JobsError::DuplicateJob(jobdata.name));
+        }
+        jobdata.parse();
+        self.order.push(jobdata.name);
+        self.data.insert(jobdata.name, jobdata);
+        return Ok(());
     }
-    pub fn add(&mut self, jobdata: JobData) {
-        self.data.push(jobdata);
+    /// Replaces existing job data with the same name, returns the old one
+    pub fn replace(&mut self, mut jobdata: JobData) -> Result<JobData, JobsError> {
+        let Some(old) = self.data.get_mut(jobdata.name) else {
+            return Err(JobsError::MissingJob(jobdata.name.to_string()));
+        };
+        jobdata.parse();
+        return Ok(std::mem::replace(old, jobdata));
+    }
+    pub fn remove(&mut self, job_name: &str) -> Result<JobData, JobsError> {
+        let Some(jobdata) = self.data.remove(job_name) else {
+            return Err(JobsError::MissingJob(job_name.to_string()));
+        };
+        self.order.retain(|name| *name != job_name);
+        return Ok(jobdata);
     }
     pub fn clear(&mut self){
         self.data.clear();
+        self.order.clear();
     } 
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+    pub fn contains(&self, job_name: &str) -> bool {
+        self.data.contains_key(job_name)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &JobData> {
+        self.order.iter().filter_map(|name| self.data.get(name))
+    }
 
-    pub fn get(&self, job_name: &'static str) -> Option<&JobData> {
-        for jd in self.data.iter(){
-            if jd.name == job_name {
-                return Some(jd);
-            }
-        }
-        return None;
+    pub fn get(&self, job_name: &str) -> Option<&JobData> {
+        self.data.get(job_name)
     }
 
     pub fn assign(
@@ -261,17 +483,20 @@ impl JobCatalog {
         commands:   &mut Commands, 
         entity:     Entity,
         job_name:   &'static str, 
+    ){
+        self.assign_with(commands, entity, job_name, JobParams::default());
+    }
+
+    pub fn assign_with(
+        &self, 
+        commands:   &mut Commands, 
+        entity:     Entity,
+        job_name:   &'static str, 
+        params:     JobParams
     ){
         commands.entity(entity).remove::<Job>();
         if let Some(jobdata) = self.get(job_name){
-            let mut job = Job::new(jobdata.clone());
-            job.set_active();
-            commands.entity(entity).insert(job);
-            if let Some(first_task) = jobdata.tasks.get_current(){
-                first_task.task.insert(commands, &entity);
-            } else {
-                error!("Could not start first task for entity: {}", entity);
-            }
+            jobdata.assign_with(commands, entity, params);
         } else {
             error!("Could not assign job: {} to entity: {}", job_name, entity);
         }
@@ -282,9 +507,18 @@ impl JobCatalog {
         &self, 
         commands: &mut Commands, 
         job_name:   &'static str
+    ) -> Option<Entity> {
+        self.start_with(commands, job_name, JobParams::default())
+    }
+
+    pub fn start_with(
+        &self, 
+        commands: &mut Commands, 
+        job_name:   &'static str,
+        params:     JobParams
     ) -> Option<Entity> {
         if let Some(jobdata) = self.get(job_name){
-            if let Some(job_entity) = jobdata.start(commands){
+            if let Some(job_entity) = jobdata.start_with(commands, params){
                 return Some(job_entity);
             }
         }
@@ -319,58 +553,147 @@ impl JobSettings {
     }
 }
 
+// How many most recently spawned entities are kept in the trigger history
+const TRIGGER_HISTORY_SPAWNED: usize = 32;
+
+/// Record of what a trigger has done so far. Used for debugging and inspecting schedules.
+#[derive(Debug, Clone, Default)]
+pub struct TriggerHistory {
+    pub fire_count:          u32,
+    pub last_fired:          Option<f64>,           // Real time (seconds since startup)
+    pub last_fired_calendar: Option<NaiveDateTime>, // In-game time
+    pub spawned:             VecDeque<Entity>       // Most recently spawned entities, oldest first
+}
+impl TriggerHistory {
+    fn record(
+        &mut self, 
+        real:      f64, 
+        calendar:  Option<NaiveDateTime>, 
+        entity:    Option<Entity>
+    ){
+        self.fire_count += 1;
+        self.last_fired = Some(real);
+        self.last_fired_calendar = calendar;
+        if let Some(entity) = entity {
+            if self.spawned.len() >= TRIGGER_HISTORY_SPAWNED {
+                self.spawned.pop_front();
+            }
+            self.spawned.push_back(entity);
+        }
+    }
+}
+
+/// Stores JobTriggers keyed by trigger_id, iterated in insertion order
 #[derive(Resource)]
 pub struct JobScheduler {
-    pub data: Vec<JobTrigger>
+    data:     HashMap<u32, JobTrigger>,
+    order:    Vec<u32>,
+    history:  HashMap<u32, TriggerHistory>
 }
 impl JobScheduler {
     fn init() -> Self {
-        JobScheduler { data: Vec::new() }
+        JobScheduler { data: HashMap::default(), order: Vec::new(), history: HashMap::default() }
+    }
+    pub fn insert(&mut self, jobtrigger: JobTrigger) -> Result<(), JobsError> {
+        if self.data.contains_key(&jobtrigger.trigger_id) {
+            return Err(JobsError::DuplicateTrigger(jobtrigger.trigger_id));
+        }
+        self.order.push(jobtrigger.trigger_id);
+        self.data.insert(jobtrigger.trigger_id, jobtrigger);
+        return Ok(());
     }
-    pub fn add(&mut self, jobtrigger: JobTrigger) {
-        self.data.push(jobtrigger);
+    /// Replaces existing trigger with the same trigger_id, returns the old one. History is kept.
+    pub fn replace(&mut self, jobtrigger: JobTrigger) -> Result<JobTrigger, JobsError> {
+        let Some(old) = self.data.get_mut(&jobtrigger.trigger_id) else {
+            return Err(JobsError::MissingTrigger(jobtrigger.trigger_id));
+        };
+        return Ok(std::mem::replace(old, jobtrigger));
+    }
+    pub fn remove(&mut self, trigger_id: u32) -> Result<JobTrigger, JobsError> {
+        let Some(jobtrigger) = self.data.remove(&trigger_id) else {
+            return Err(JobsError::MissingTrigger(trigger_id));
+        };
+        self.order.retain(|id| *id != trigger_id);
+        self.history.remove(&trigger_id);
+        return Ok(jobtrigger);
     }
     pub fn clear(&mut self){
         self.data.clear();
+        self.order.clear();
+        self.history.clear();
     } 
-    pub fn get(&self, trigger_id: u32) -> Option<JobTrigger> {
-        for jobtrigger in self.data.iter() {
-            if jobtrigger.trigger_id == trigger_id {
-                return Some(jobtrigger.clone());
-            }
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+    pub fn contains(&self, trigger_id: u32) -> bool {
+        self.data.contains_key(&trigger_id)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &JobTrigger> {
+        self.order.iter().filter_map(|trigger_id| self.data.get(trigger_id))
+    }
+    pub fn history(&self, trigger_id: u32) -> Option<&TriggerHistory> {
+        self.history.get(&trigger_id)
+    }
+    pub fn clear_history(&mut self){
+        self.history.clear();
+    }
+    fn record(
+        &mut self, 
+        trigger_id: u32, 
+        real:       f64, 
+        calendar:   Option<NaiveDateTime>, 
+        entity:     Option<Entity>
+    ){
+        self.history.entry(trigger_id).or_default().record(real, calendar, entity);
+    }
+    /// Next expected in-game fire time of the trigger. Only Cron and Calendar schedules can be predicted.
+    pub fn next_fire(&self, trigger_id: u32, calendar: &Calendar) -> Option<NaiveDateTime> {
+        let jobtrigger = self.data.get(&trigger_id)?;
+        if !jobtrigger.active || jobtrigger.after.is_some() {
+            return None;
         }
-        error!("Missing job trigger id in the scheduler: {}", trigger_id);
-        return None;
+        match &jobtrigger.schedule {
+            JobSchedule::Cron(cron) => next_cron_time(cron, calendar),
+            JobSchedule::Calendar(schedule) => schedule.next_time(calendar),
+            _ => None
+        }
+    }
+    pub fn get(&self, trigger_id: u32) -> Option<&JobTrigger> {
+        self.data.get(&trigger_id)
+    }
+    pub fn get_mut(&mut self, trigger_id: u32) -> Option<&mut JobTrigger> {
+        self.data.get_mut(&trigger_id)
     }
     pub fn deactivate_all(&mut self){
         info!(" [JOBS DEBUG] Deactivate all triggers");
-        for jobtrigger in self.data.iter_mut(){
+        for jobtrigger in self.data.values_mut(){
             jobtrigger.active = false;
         }
     }
     pub fn activate_all(&mut self){
         info!(" [JOBS DEBUG] Activate all triggers");
-        for jobtrigger in self.data.iter_mut(){
+        for jobtrigger in self.data.values_mut(){
             jobtrigger.active = true;
         }
     }
-    pub fn activate(&mut self, trigger_id: &u32){
+    pub fn activate(&mut self, trigger_id: u32) -> Result<(), JobsError> {
         info!(" [JOBS DEBUG] Activate trigger: {}", trigger_id);
-        for jobtrigger in self.data.iter_mut(){
-            if &jobtrigger.trigger_id == trigger_id {
-                jobtrigger.active = true;
-                break;
-            }
-        }
+        let Some(jobtrigger) = self.data.get_mut(&trigger_id) else {
+            return Err(JobsError::MissingTrigger(trigger_id));
+        };
+        jobtrigger.active = true;
+        return Ok(());
     }
-    pub fn deactivate(&mut self, trigger_id: &u32){
+    pub fn deactivate(&mut self, trigger_id: u32) -> Result<(), JobsError> {
         info!(" [JOBS DEBUG] Deactivate trigger: {}", trigger_id);
-        for jobtrigger in self.data.iter_mut(){
-            if &jobtrigger.trigger_id == trigger_id {
-                jobtrigger.active = false;
-                break;
-            }
-        }
+        let Some(jobtrigger) = self.data.get_mut(&trigger_id) else {
+            return Err(JobsError::MissingTrigger(trigger_id));
+        };
+        jobtrigger.active = false;
+        return Ok(());
     }
 }
 
@@ -379,14 +702,91 @@ impl JobScheduler {
 #[component(storage = "SparseSet")]
 pub struct JobPaused;
 
+#[derive(Debug, Clone, Reflect)]
+pub struct QueuedJob {
+    pub name:   &'static str,
+    pub params: JobParams
+}
+
+/// Jobs waiting to run on the entity after the current one ends
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct JobQueue {
+    data: VecDeque<QueuedJob>
+}
+impl JobQueue {
+    pub fn push(&mut self, name: &'static str) {
+        self.push_with(name, JobParams::default());
+    }
+    pub fn push_with(&mut self, name: &'static str, params: JobParams) {
+        self.data.push_back(QueuedJob{name, params});
+    }
+    pub fn push_front(&mut self, name: &'static str, params: JobParams) {
+        self.data.push_front(QueuedJob{name, params});
+    }
+    pub fn pop(&mut self) -> Option<QueuedJob> {
+        self.data.pop_front()
+    }
+    pub fn get(&self, index: usize) -> Option<&QueuedJob> {
+        self.data.get(index)
+    }
+    pub fn remove(&mut self, index: usize) -> Option<QueuedJob> {
+        self.data.remove(index)
+    }
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a < self.data.len() && b < self.data.len() {
+            self.data.swap(a, b);
+        }
+    }
+    /// Moves queued job from one position to another
+    pub fn move_to(&mut self, from: usize, to: usize) {
+        if let Some(queued) = self.data.remove(from) {
+            let to = to.min(self.data.len());
+            self.data.insert(to, queued);
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &QueuedJob> {
+        self.data.iter()
+    }
+}
+
+/// Query filter for task systems, skips entities with paused jobs
+pub type JobNotPaused = Without<JobPaused>;
+
+/// Selects jobs for batch operations
+#[derive(Debug, Clone)]
+pub enum JobSelector {
+    All,
+    Name(&'static str),
+    Entities(Vec<Entity>)
+}
+impl JobSelector {
+    pub fn matches(&self, entity: Entity, job: &Job) -> bool {
+        match self {
+            JobSelector::All => true,
+            JobSelector::Name(name) => job.name() == *name,
+            JobSelector::Entities(entities) => entities.contains(&entity)
+        }
+    }
+}
+
+#[derive(Message)]
+pub struct PauseJobsEvent {
+    pub selector: JobSelector
+}
 
-#[derive(PartialEq, Copy, Clone, Debug, Reflect)]
-pub enum JobStatus {
-    ToDo,
-    Active,
-    Done,
-    Paused,
-    Inactive
+#[derive(Message)]
+pub struct ResumeJobsEvent {
+    pub selector: JobSelector
 }
 
 #[derive(Asset, TypePath, Clone, Debug)]
@@ -399,20 +799,99 @@ pub struct JobTrigger {
     pub name:          &'static str,
     pub trigger_id:    u32,
     pub schedule:      JobSchedule,
-    pub active:        bool
+    pub active:        bool,
+    pub chance:        Option<f32>,                // Probability of firing when schedule matches, always if None
+    pub pool:          Vec<(&'static str, u32)>,   // Weighted job names to pick from instead of name
+    pub after:         Option<JobAfter>            // Fire when another job ends instead of on schedule
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobOutcome {
+    Completed,
+    Failed,
+    Cancelled,  // Cancelled for other reason than failure
+    Any
+}
+
+/// Chains trigger to the end of another job
+#[derive(Clone, Debug)]
+pub struct JobAfter {
+    pub job:           &'static str,
+    pub outcome:       JobOutcome,
+    pub same_entity:   bool,                 // Assign to the entity that ended the job instead of starting new one
+    pub pass_as:       Option<&'static str>  // Pass the entity that ended the job as parameter with this key
+}
+impl JobTrigger {
+    pub fn new(
+        name:       &'static str, 
+        trigger_id: u32, 
+        schedule:   JobSchedule
+    ) -> Self {
+        JobTrigger{
+            name, 
+            trigger_id, 
+            schedule, 
+            active: true, 
+            chance: None, 
+            pool: Vec::new(),
+            after: None
+        }
+    }
+    pub fn with_after(
+        mut self, 
+        job:         &'static str, 
+        outcome:     JobOutcome, 
+        same_entity: bool
+    ) -> Self {
+        self.after = Some(JobAfter{job, outcome, same_entity, pass_as: None});
+        self
+    }
+    /// For chained triggers, passes the entity that ended the job as parameter
+    pub fn with_pass_as(mut self, key: &'static str) -> Self {
+        if let Some(after) = &mut self.after {
+            after.pass_as = Some(key);
+        }
+        self
+    }
+    pub fn with_chance(mut self, chance: f32) -> Self {
+        self.chance = Some(chance);
+        self
+    }
+    pub fn with_pool(mut self, pool: Vec<(&'static str, u32)>) -> Self {
+        self.pool = pool;
+        self
+    }
+    /// Rolls the chance and picks the job name. None if trigger should not fire this time.
+    /// Uses the trigger's own stream, so adding or removing other triggers doesn't change its rolls.
+    pub fn roll(&self, job_rng: &mut JobRng) -> Option<&'static str> {
+        let rng = job_rng.trigger(self.trigger_id);
+        if let Some(probability) = self.chance {
+            if !chance(rng, probability) {
+                return None;
+            }
+        }
+        if self.pool.is_empty() {
+            return Some(self.name);
+        }
+        let index = weighted(rng, self.pool.iter().map(|(_name, weight)| *weight))?;
+        return Some(self.pool[index].0);
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Reflect)]
 pub enum JobSchedule {      
-    Instant,             // Start instantly       
-    Cron(Cron),          // Waiting for Cron 
-    Delay(u8),           // Delay in in-game hours
-    RealDelay(f32)       // Real time delay  
+    Instant,                      // Start instantly       
+    Cron(Cron),                   // Waiting for Cron 
+    Calendar(CalendarSchedule),   // Waiting for in-game minute matching weekdays, dates etc.
+    Delay(u32),                   // Delay in in-game hours
+    DelayMinutes(u32),            // Delay in in-game minutes
+    RealDelay(f32)                // Real time delay  
 } 
 impl JobSchedule {
     pub fn parse(&mut self) {
         match self {
             JobSchedule::Cron(cron) => {cron.parse()}
+            JobSchedule::Calendar(schedule) => {schedule.parse()}
             _ => {}
         }
     }
@@ -421,59 +900,257 @@ impl JobSchedule {
 // Update jobs. Triggers every hour from calendar.
 fn trigger_jobs_calendar(
     mut commands:         Commands,
+    time:                 Res<Time>,
     calendar:             Res<Calendar>,
-    job_scheduler:        Res<JobScheduler>,
+    mut job_scheduler:    ResMut<JobScheduler>,
+    mut job_rng:          ResMut<JobRng>,
     job_catalog:          Res<JobCatalog>
 ){
+    let mut fired: Vec<(u32, Option<Entity>)> = Vec::new();
+    for job_trigger in job_scheduler.iter(){
 
-    for job_trigger in job_scheduler.data.iter(){
-
-        if !job_trigger.active {
+        if !job_trigger.active || job_trigger.after.is_some() {
             continue;
         }
 
         match &job_trigger.schedule {
             JobSchedule::Cron(cron) => {
                 if cron.is_time(&calendar){
-                    job_catalog.start(&mut commands, job_trigger.name);
+                    let Some(job_name) = job_trigger.roll(&mut job_rng) else {continue};
+                    let entity = job_catalog.start(&mut commands, job_name);
+                    fired.push((job_trigger.trigger_id, entity));
                 }
              }
             _=> {}
         }
     }
 
+    let now = calendar_now(&calendar);
+    for (trigger_id, entity) in fired {
+        job_scheduler.record(trigger_id, time.elapsed_secs_f64(), Some(now), entity);
+    }
+}
+
+// Update jobs with calendar schedules. Checks every in-game minute that passed since last frame,
+// but fires each trigger at most once per frame, missed occurrences are not replayed.
+fn trigger_jobs_calendar_minutes(
+    mut commands:         Commands,
+    time:                 Res<Time>,
+    calendar:             Res<Calendar>,
+    calendar_time:        Res<JobCalendarTime>,
+    mut job_scheduler:    ResMut<JobScheduler>,
+    mut job_rng:          ResMut<JobRng>,
+    job_catalog:          Res<JobCatalog>
+){
+    let minutes = calendar_time.minutes();
+    if minutes.is_empty(){
+        return;
+    }
+    let mut probe = CalendarProbe::new(&calendar);
+    let mut fired: Vec<(u32, NaiveDateTime, Option<Entity>)> = Vec::new();
+    for job_trigger in job_scheduler.iter(){
+
+        if !job_trigger.active || job_trigger.after.is_some() {
+            continue;
+        }
+
+        match &job_trigger.schedule {
+            JobSchedule::Calendar(schedule) => {
+                let Some(minute) = minutes.iter().rev().find(|minute| schedule.is_time_with(&mut probe, **minute)) else {continue};
+                let Some(job_name) = job_trigger.roll(&mut job_rng) else {continue};
+                let entity = job_catalog.start(&mut commands, job_name);
+                fired.push((job_trigger.trigger_id, *minute, entity));
+            }
+            _=> {}
+        }
+    }
+
+    for (trigger_id, minute, entity) in fired {
+        job_scheduler.record(trigger_id, time.elapsed_secs_f64(), Some(minute), entity);
+    }
 }
 
 // Updates jobs on real time
 fn trigger_jobs_time(
     mut commands:         Commands,
-    job_scheduler:        Res<JobScheduler>,
+    time:                 Res<Time>,
+    calendar:             Option<Res<Calendar>>,
+    mut job_scheduler:    ResMut<JobScheduler>,
+    mut job_rng:          ResMut<JobRng>,
     job_catalog:          Res<JobCatalog>
 ){
-    for job_trigger in job_scheduler.data.iter(){
+    let mut fired: Vec<(u32, Option<Entity>)> = Vec::new();
+    for job_trigger in job_scheduler.iter(){
 
-        if !job_trigger.active {
+        if !job_trigger.active || job_trigger.after.is_some() {
             continue;
         }
 
         match &job_trigger.schedule {
             JobSchedule::Instant => {
-                job_catalog.start(&mut commands, job_trigger.name);
+                let Some(job_name) = job_trigger.roll(&mut job_rng) else {continue};
+                let entity = job_catalog.start(&mut commands, job_name);
+                fired.push((job_trigger.trigger_id, entity));
             }
             _=> {}
         }
     }
+
+    let now = calendar.map(|calendar| calendar_now(&calendar));
+    for (trigger_id, entity) in fired {
+        job_scheduler.record(trigger_id, time.elapsed_secs_f64(), now, entity);
+    }
+}
+
+// Fires triggers chained to jobs that just ended
+fn trigger_jobs_after(
+    mut commands:         Commands,
+    time:                 Res<Time>,
+    calendar:             Option<Res<Calendar>>,
+    mut ended:            MessageReader<JobEnded>,
+    entities:             Query<Entity>,
+    mut job_scheduler:    ResMut<JobScheduler>,
+    mut job_rng:          ResMut<JobRng>,
+    job_catalog:          Res<JobCatalog>
+){
+    let ended: Vec<JobEnded> = ended.read().cloned().collect();
+
+    let mut fired: Vec<(u32, Option<Entity>)> = Vec::new();
+    for JobEnded{entity: ended_entity, name: ended_name, outcome} in ended {
+        for job_trigger in job_scheduler.iter(){
+
+            if !job_trigger.active {
+                continue;
+            }
+            let Some(after) = &job_trigger.after else {continue};
+            if after.job != ended_name || (after.outcome != JobOutcome::Any && after.outcome != outcome) {
+                continue;
+            }
+            if after.same_entity && !entities.contains(ended_entity) {
+                // Entity despawned with its job
+                continue;
+            }
+            let Some(job_name) = job_trigger.roll(&mut job_rng) else {continue};
+            let mut params = JobParams::default();
+            if let Some(key) = after.pass_as {
+                params.set(key, ended_entity);
+            }
+            if after.same_entity {
+                job_catalog.assign_with(&mut commands, ended_entity, job_name, params);
+                fired.push((job_trigger.trigger_id, Some(ended_entity)));
+            } else {
+                let entity = job_catalog.start_with(&mut commands, job_name, params);
+                fired.push((job_trigger.trigger_id, entity));
+            }
+        }
+    }
+
+    let now = calendar.map(|calendar| calendar_now(&calendar));
+    for (trigger_id, entity) in fired {
+        job_scheduler.record(trigger_id, time.elapsed_secs_f64(), now, entity);
+    }
+}
+
+
+// Ages running jobs and ends the ones past their lifetime
+fn expire_jobs(
+    mut commands:       Commands,
+    time:               Res<Time>,
+    calendar:           Option<Res<Calendar>>,
+    calendar_time:      Res<JobCalendarTime>,
+    mut jobs:           Query<(Entity, &mut Job), JobNotPaused>
+){
+    let minutes = calendar_time.minutes();
+    let mut probe = calendar.as_ref().map(|calendar| CalendarProbe::new(calendar));
+    for (entity, mut job) in jobs.iter_mut(){
+        if job.is_finished(){
+            continue;
+        }
+        // Aging alone shouldn't mark every running job as Changed
+        job.bypass_change_detection().add_age(time.delta_secs(), minutes.len() as u32);
+
+        if job.is_cleaning(){
+            continue;
+        }
+        let Some(expiry) = job.data.expiry.clone() else {continue};
+        let expired = match &expiry.lifetime {
+            JobLifetime::RealSecs(secs) => job.age() >= *secs,
+            JobLifetime::Minutes(m) => job.game_age() >= *m,
+            JobLifetime::Hours(h) => job.game_age() >= h*60,
+            JobLifetime::Days(d) => job.game_age() >= d*60*24,
+            JobLifetime::Until(schedule) => {
+                probe.as_mut().is_some_and(|probe| minutes.iter().any(|minute| schedule.is_time_with(probe, *minute)))
+            }
+        };
+        if !expired {
+            continue;
+        }
+        // Expires only once, in case JobOnFail keeps the job running
+        job.data.expiry = None;
+        #[cfg(feature="verbose")]
+        info!(" [JOBS] Job {} expired on entity {:?}", job.name(), entity);
+        match expiry.action {
+            JobExpireAction::Cancel => job.cancel_with(&mut commands, &entity, JobCancelReason::Expired),
+            JobExpireAction::Fail => job.fail_with(&mut commands, &entity, "expired")
+        }
+    }
+}
+
+// Sets finish time on jobs retained after they ended
+fn stamp_finished_jobs(
+    time:     Res<Time>,
+    mut jobs: Query<&mut Job, Changed<Job>>
+){
+    for mut job in jobs.iter_mut(){
+        if job.is_finished() && job.finished_at().is_none(){
+            job.set_finished_at(time.elapsed_secs_f64());
+        }
+    }
 }
 
+fn fail_job(
+    mut commands:       Commands,
+    mut fail_job:       MessageReader<FailJobEvent>,
+    mut jobs:           Query<&mut Job>
+){
+    for ev in fail_job.read(){
+        if let Ok(mut job) = jobs.get_mut(ev.entity) && !job.is_finished(){
+            job.fail_with(&mut commands, &ev.entity, ev.reason.clone());
+        }
+    }
+}
 
 fn stop_job(
     mut commands:       Commands,
-    mut stop_job:       MessageReader<StopJobEvent>
+    mut stop_job:       MessageReader<StopJobEvent>,
+    mut jobs:           Query<&mut Job>,
+    mut queues:         Query<&mut JobQueue>
 ){
     for ev in stop_job.read(){
+        if !ev.keep_queue && let Ok(mut queue) = queues.get_mut(ev.entity){
+            queue.clear();
+        }
         #[cfg(feature="verbose")]
         info!(" [JOBS] Removing job from entity: {:?}", ev.entity);
-        commands.entity(ev.entity).remove::<Job>();
+        let Ok(mut job) = jobs.get_mut(ev.entity) else {continue};
+        if job.is_finished(){
+            commands.entity(ev.entity).remove::<Job>();
+            continue;
+        }
+        match ev.mode {
+            StopMode::Remove => {
+                commands.write_message(JobCancelled{
+                    entity: ev.entity, 
+                    name: job.data.name, 
+                    task_id: job.data.tasks.current_task_id,
+                    reason: JobCancelReason::Stopped
+                });
+                commands.entity(ev.entity).remove::<Job>();
+            }
+            StopMode::Cancel => {
+                job.cancel_with(&mut commands, &ev.entity, JobCancelReason::Stopped);
+            }
+        }
     }
 
 }
@@ -487,7 +1164,88 @@ fn start_job(
         #[cfg(feature="verbose")]
         info!(" [JOBS] Adding job {} to entity {:?}", ev.name, ev.entity);
 
-        jobs_catalog.assign(&mut commands, ev.entity, ev.name);
+        jobs_catalog.assign_with(&mut commands, ev.entity, ev.name, ev.params.clone());
+    }
+}
+
+fn enqueue_job(
+    mut commands:       Commands,
+    mut enqueue_job:    MessageReader<EnqueueJobEvent>,
+    mut queues:         Query<&mut JobQueue>
+){
+    // Queues inserted in this system are not visible in the query until commands are applied
+    let mut new_queues: HashMap<Entity, JobQueue> = HashMap::default();
+    for ev in enqueue_job.read(){
+        if let Ok(mut queue) = queues.get_mut(ev.entity){
+            queue.push_with(ev.name, ev.params.clone());
+        } else {
+            new_queues.entry(ev.entity).or_default().push_with(ev.name, ev.params.clone());
+        }
+    }
+    for (entity, queue) in new_queues {
+        commands.entity(entity).try_insert(queue);
+    }
+}
+
+// Starts next queued job on entities that have no job running
+fn advance_job_queues(
+    mut commands:       Commands,
+    jobs_catalog:       Res<JobCatalog>,
+    mut queues:         Query<(Entity, &mut JobQueue, Option<&Job>)>
+){
+    for (entity, mut queue, job) in queues.iter_mut(){
+        if job.is_some_and(|job| !job.is_finished()){
+            continue;
+        }
+        if let Some(queued) = queue.pop(){
+            #[cfg(feature="verbose")]
+            info!(" [JOBS] Starting queued job {} on entity {:?}", queued.name, entity);
+            jobs_catalog.assign_with(&mut commands, entity, queued.name, queued.params);
+        }
+    }
+}
+
+fn pause_jobs(
+    mut commands:       Commands,
+    mut pause:          MessageReader<PauseJobsEvent>,
+    mut resume:         MessageReader<ResumeJobsEvent>,
+    mut jobs:           Query<(Entity, &mut Job)>
+){
+    for ev in pause.read(){
+        for (entity, mut job) in jobs.iter_mut(){
+            if !job.is_finished() && ev.selector.matches(entity, &job){
+                job.pause(&mut commands, &entity);
+            }
+        }
+    }
+    for ev in resume.read(){
+        for (entity, mut job) in jobs.iter_mut(){
+            if !job.is_finished() && ev.selector.matches(entity, &job){
+                job.unpause(&mut commands, &entity);
+            }
+        }
+    }
+}
+
+/// Pauses all jobs on entities matching the filter, e.g. `pause_jobs_with::<With<Customer>>`
+pub fn pause_jobs_with<F: QueryFilter>(
+    mut commands:       Commands,
+    mut jobs:           Query<(Entity, &mut Job), (F, JobNotPaused)>
+){
+    for (entity, mut job) in jobs.iter_mut(){
+        if !job.is_finished(){
+            job.pause(&mut commands, &entity);
+        }
+    }
+}
+
+/// Resumes all jobs on entities matching the filter, e.g. `resume_jobs_with::<With<Customer>>`
+pub fn resume_jobs_with<F: QueryFilter>(
+    mut commands:       Commands,
+    mut jobs:           Query<(Entity, &mut Job), (F, With<JobPaused>)>
+){
+    for (entity, mut job) in jobs.iter_mut(){
+        job.unpause(&mut commands, &entity);
     }
 }
 
@@ -495,3 +1253,132 @@ fn start_job(
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 struct JobDebug;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jobdata(name: &'static str) -> JobData {
+        JobData{name, ..Default::default()}
+    }
+
+    fn names(catalog: &JobCatalog) -> Vec<&'static str> {
+        catalog.iter().map(|jobdata| jobdata.name).collect()
+    }
+
+    fn ids(scheduler: &JobScheduler) -> Vec<u32> {
+        scheduler.iter().map(|jobtrigger| jobtrigger.trigger_id).collect()
+    }
+
+    #[test]
+    fn catalog_insert_and_duplicates() {
+        let mut catalog = JobCatalog::init();
+        assert!(catalog.insert(jobdata("A")).is_ok());
+        assert!(catalog.insert(jobdata("B")).is_ok());
+        assert_eq!(catalog.insert(jobdata("A")), Err(JobsError::DuplicateJob("A")));
+        assert_eq!(catalog.len(), 2);
+        assert!(catalog.contains("B"));
+        assert!(catalog.get("C").is_none());
+    }
+
+    #[test]
+    fn catalog_missing_jobs() {
+        let mut catalog = JobCatalog::init();
+        assert_eq!(catalog.replace(jobdata("A")).err(), Some(JobsError::MissingJob("A".to_string())));
+        assert_eq!(catalog.remove("A").err(), Some(JobsError::MissingJob("A".to_string())));
+        assert!(catalog.is_empty());
+    }
+
+    #[test]
+    fn catalog_keeps_order() {
+        let mut catalog = JobCatalog::init();
+        for name in ["A", "B", "C", "D"] {
+            catalog.insert(jobdata(name)).unwrap();
+        }
+        assert_eq!(catalog.remove("B").unwrap().name, "B");
+        assert_eq!(names(&catalog), vec!["A", "C", "D"]);
+
+        let mut replacement = jobdata("C");
+        replacement.retain = true;
+        assert!(!catalog.replace(replacement).unwrap().retain);
+        assert!(catalog.get("C").unwrap().retain);
+        assert_eq!(names(&catalog), vec!["A", "C", "D"]);
+
+        catalog.insert(jobdata("B")).unwrap();
+        assert_eq!(names(&catalog), vec!["A", "C", "D", "B"]);
+    }
+
+    #[test]
+    fn scheduler_insert_and_duplicates() {
+        let mut scheduler = JobScheduler::init();
+        assert!(scheduler.insert(JobTrigger::new("A", 1, JobSchedule::Instant)).is_ok());
+        assert_eq!(scheduler.insert(JobTrigger::new("B", 1, JobSchedule::Instant)), Err(JobsError::DuplicateTrigger(1)));
+        assert_eq!(scheduler.len(), 1);
+        assert_eq!(scheduler.get(1).unwrap().name, "A");
+    }
+
+    #[test]
+    fn scheduler_missing_triggers() {
+        let mut scheduler = JobScheduler::init();
+        assert_eq!(scheduler.replace(JobTrigger::new("A", 1, JobSchedule::Instant)).err(), Some(JobsError::MissingTrigger(1)));
+        assert_eq!(scheduler.remove(1).err(), Some(JobsError::MissingTrigger(1)));
+        assert_eq!(scheduler.activate(1), Err(JobsError::MissingTrigger(1)));
+        assert_eq!(scheduler.deactivate(1), Err(JobsError::MissingTrigger(1)));
+    }
+
+    #[test]
+    fn scheduler_keeps_order_and_history() {
+        let mut scheduler = JobScheduler::init();
+        for trigger_id in [3, 1, 2] {
+            scheduler.insert(JobTrigger::new("A", trigger_id, JobSchedule::Instant)).unwrap();
+        }
+        scheduler.record(1, 0.0, None, None);
+        scheduler.record(2, 0.0, None, None);
+
+        scheduler.remove(1).unwrap();
+        assert_eq!(ids(&scheduler), vec![3, 2]);
+        assert!(scheduler.history(1).is_none());
+
+        // Replace keeps position and history
+        scheduler.replace(JobTrigger::new("B", 2, JobSchedule::Instant)).unwrap();
+        assert_eq!(ids(&scheduler), vec![3, 2]);
+        assert_eq!(scheduler.get(2).unwrap().name, "B");
+        assert_eq!(scheduler.history(2).unwrap().fire_count, 1);
+
+        scheduler.insert(JobTrigger::new("A", 1, JobSchedule::Instant)).unwrap();
+        assert_eq!(ids(&scheduler), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn roll_does_not_depend_on_other_triggers() {
+        let pooled = JobTrigger::new("A", 1, JobSchedule::Instant)
+            .with_chance(0.5)
+            .with_pool(vec![("B", 1), ("C", 2)]);
+        let other = JobTrigger::new("D", 2, JobSchedule::Instant).with_chance(0.5);
+
+        let mut alone = JobRng::new(42);
+        let expected: Vec<Option<&'static str>> = (0..20).map(|_| pooled.roll(&mut alone)).collect();
+
+        let mut shared = JobRng::new(42);
+        let rolled: Vec<Option<&'static str>> = (0..20).map(|_| {
+            other.roll(&mut shared);
+            pooled.roll(&mut shared)
+        }).collect();
+        assert_eq!(rolled, expected);
+        assert!(expected.contains(&None) && expected.contains(&Some("C")));
+    }
+
+    #[test]
+    fn scheduler_activation() {
+        let mut scheduler = JobScheduler::init();
+        scheduler.insert(JobTrigger::new("A", 1, JobSchedule::Instant)).unwrap();
+        scheduler.insert(JobTrigger::new("B", 2, JobSchedule::Instant)).unwrap();
+        scheduler.deactivate(1).unwrap();
+        assert!(!scheduler.get(1).unwrap().active);
+        assert!(scheduler.get(2).unwrap().active);
+        scheduler.activate(1).unwrap();
+        assert!(scheduler.get(1).unwrap().active);
+        scheduler.deactivate_all();
+        assert!(scheduler.iter().all(|jobtrigger| !jobtrigger.active));
+    }
+}
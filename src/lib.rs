@@ -2,13 +2,23 @@
 #[cfg(feature="common")]
 pub mod common;
 
+mod calendar;
 mod jobs;
+mod rng;
 mod types;
 
 pub mod prelude {
-    pub use crate::types::{Job, JobData, JobTasks, Task, PGTask, JobOnFail};
+    pub use crate::types::{Job, JobData, JobTasks, Task, PGTask, JobOnFail, JobStatus,
+        JobValue, JobParams, JobBlackboard, JobArg, FromJobValue, JobExpiry, JobLifetime, JobExpireAction,
+        ReflectedValue};
     pub use crate::jobs::{PGJobsPlugin, JobSettings, JobScheduler, JobSchedule,
-        StopJobEvent, StartJobEvent, JobCatalog, JobPaused, TaskSets, PGJobsSet, if_jobs_active}; 
+        StopJobEvent, StopMode, StartJobEvent, JobCatalog, JobPaused, JobNotPaused, TaskSets, PGJobsSet, if_jobs_active,
+        JobTrigger, TriggerHistory, JobAfter, JobOutcome, JobsError,
+        JobStarted, JobTaskChanged, JobCompleted, JobFailed, JobCancelled, JobCancelReason, JobEnded,
+        JobSelector, PauseJobsEvent, ResumeJobsEvent, pause_jobs_with, resume_jobs_with,
+        JobQueue, QueuedJob, EnqueueJobEvent, FailJobEvent}; 
+    pub use crate::calendar::{CalendarSchedule, DateRange, JobCalendarTime};
+    pub use crate::rng::JobRng;
 
     #[cfg(feature="common")]
     pub use crate::common::*;
@@ -0,0 +1,236 @@
+// Seedable random numbers shared by jobs, so runs can be reproduced
+use bevy::ecs::entity::{Entities, Entity};
+use bevy::ecs::resource::Resource;
+use bevy::ecs::system::ResMut;
+use bevy::platform::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+// Mixed into the seed of trigger streams, so they differ from entity streams with the same number
+const TRIGGER_STREAMS: u64 = 0x7472_6967_6765_7273;
+
+/// Random number source for triggers and tasks. Same seed gives the same sequence.
+/// Besides the global stream, every entity, job name and trigger gets its own stream derived from the seed,
+/// so randomness of one entity does not depend on how many others drew numbers before it.
+/// Entity streams live as long as the entity, so consecutive jobs on it continue the sequence.
+#[derive(Resource)]
+pub struct JobRng {
+    seed:     u64,
+    rng:      StdRng,
+    entities: HashMap<Entity, StdRng>,
+    jobs:     HashMap<String, StdRng>,
+    triggers: HashMap<u32, StdRng>
+}
+impl JobRng {
+    pub fn new(seed: u64) -> Self {
+        JobRng { 
+            seed, 
+            rng: StdRng::seed_from_u64(seed), 
+            entities: HashMap::default(), 
+            jobs: HashMap::default(),
+            triggers: HashMap::default()
+        }
+    }
+    /// Seeded from OS entropy, the seed is kept so the run can be repeated
+    pub fn from_entropy() -> Self {
+        JobRng::new(rand::rng().random())
+    }
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    /// Restarts global and all derived streams from the new seed
+    pub fn reseed(&mut self, seed: u64) {
+        *self = JobRng::new(seed);
+    }
+    /// Global stream
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+    /// Stream of the entity
+    pub fn entity(&mut self, entity: Entity) -> &mut StdRng {
+        let seed = derive_seed(self.seed, entity.to_bits());
+        self.entities.entry(entity).or_insert_with(|| StdRng::seed_from_u64(seed))
+    }
+    /// Stream of the job name
+    pub fn job(&mut self, name: &str) -> &mut StdRng {
+        if !self.jobs.contains_key(name) {
+            let seed = derive_seed(self.seed, hash_str(name));
+            self.jobs.insert(name.to_string(), StdRng::seed_from_u64(seed));
+        }
+        self.jobs.get_mut(name).unwrap()
+    }
+    /// Stream of the trigger, used to roll its chance and pool
+    pub fn trigger(&mut self, trigger_id: u32) -> &mut StdRng {
+        let seed = derive_seed(derive_seed(self.seed, TRIGGER_STREAMS), trigger_id as u64);
+        self.triggers.entry(trigger_id).or_insert_with(|| StdRng::seed_from_u64(seed))
+    }
+    /// Drops the entity stream, next use starts it again from the seed
+    pub fn forget(&mut self, entity: Entity) {
+        self.entities.remove(&entity);
+    }
+    /// True with given probability (0.0..=1.0)
+    pub fn chance(&mut self, p: f32) -> bool {
+        chance(&mut self.rng, p)
+    }
+    /// Index picked from the list of weights, None if all weights are 0
+    pub fn weighted(&mut self, weights: impl Iterator<Item = u32> + Clone) -> Option<usize> {
+        weighted(&mut self.rng, weights)
+    }
+}
+
+/// True with given probability (0.0..=1.0)
+pub fn chance(rng: &mut impl Rng, p: f32) -> bool {
+    if p >= 1.0 {
+        return true;
+    }
+    if p <= 0.0 {
+        return false;
+    }
+    rng.random::<f32>() < p
+}
+
+/// Index picked from the list of weights, None if all weights are 0
+pub fn weighted(rng: &mut impl Rng, weights: impl Iterator<Item = u32> + Clone) -> Option<usize> {
+    let total: u32 = weights.clone().sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.random_range(0..total);
+    for (index, weight) in weights.enumerate(){
+        if roll < weight {
+            return Some(index);
+        }
+        roll -= weight;
+    }
+    return None;
+}
+
+// Drops streams of despawned entities, so the map doesn't grow forever
+pub(crate) fn prune_job_rng(
+    mut job_rng: ResMut<JobRng>,
+    entities:    &Entities
+){
+    if job_rng.entities.keys().all(|entity| entities.contains(*entity)) {
+        return;
+    }
+    job_rng.entities.retain(|entity, _| entities.contains(*entity));
+}
+
+// SplitMix64 step, mixes the key into the seed
+fn derive_seed(seed: u64, key: u64) -> u64 {
+    let mut z = seed ^ key.wrapping_mul(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// FNV-1a, stable between runs and platforms unlike std hashers
+fn hash_str(s: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in s.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draws(rng: &mut StdRng) -> Vec<u32> {
+        (0..8).map(|_| rng.random()).collect()
+    }
+
+    #[test]
+    fn derive_seed_is_stable() {
+        assert_eq!(derive_seed(42, 7), derive_seed(42, 7));
+        assert_ne!(derive_seed(42, 7), derive_seed(42, 8));
+        assert_ne!(derive_seed(42, 7), derive_seed(43, 7));
+        assert_eq!(hash_str("Job"), hash_str("Job"));
+        assert_ne!(hash_str("Job"), hash_str("Jobs"));
+    }
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = JobRng::new(42);
+        let mut b = JobRng::new(42);
+        assert_eq!(draws(a.rng()), draws(b.rng()));
+        assert_eq!(draws(a.job("Patrol")), draws(b.job("Patrol")));
+        assert_ne!(draws(JobRng::new(42).rng()), draws(JobRng::new(43).rng()));
+    }
+
+    #[test]
+    fn entity_streams_are_independent() {
+        let first = Entity::from_raw_u32(1).unwrap();
+        let second = Entity::from_raw_u32(2).unwrap();
+
+        let mut a = JobRng::new(42);
+        let expected = draws(a.entity(second));
+
+        // Draws of other entities and the global stream don't shift the entity stream
+        let mut b = JobRng::new(42);
+        draws(b.entity(first));
+        draws(b.rng());
+        assert_eq!(draws(b.entity(second)), expected);
+
+        // Forgotten stream starts again from the seed
+        b.forget(second);
+        assert_eq!(draws(b.entity(second)), expected);
+        assert_ne!(draws(b.entity(first)), expected);
+    }
+
+    #[test]
+    fn trigger_streams_are_independent() {
+        let mut a = JobRng::new(42);
+        let expected = draws(a.trigger(2));
+
+        let mut b = JobRng::new(42);
+        draws(b.trigger(1));
+        draws(b.rng());
+        assert_eq!(draws(b.trigger(2)), expected);
+        assert_ne!(draws(JobRng::new(42).trigger(1)), expected);
+        assert_ne!(draws(JobRng::new(42).entity(Entity::from_raw_u32(2).unwrap())), expected);
+    }
+
+    #[test]
+    fn prune_keeps_live_entities() {
+        let mut world = bevy::ecs::world::World::new();
+        let alive = world.spawn_empty().id();
+        let dead = world.spawn_empty().id();
+        let mut job_rng = JobRng::new(42);
+        let expected = draws(&mut job_rng.entity(alive).clone());
+        draws(job_rng.entity(alive));
+        draws(job_rng.entity(dead));
+        world.despawn(dead);
+        world.insert_resource(job_rng);
+
+        world.run_system_cached(prune_job_rng).unwrap();
+        let mut job_rng = world.remove_resource::<JobRng>().unwrap();
+        assert_eq!(job_rng.entities.len(), 1);
+        // Live stream continues instead of starting over
+        assert_ne!(draws(job_rng.entity(alive)), expected);
+    }
+
+    #[test]
+    fn weighted_picks() {
+        let mut rng = StdRng::seed_from_u64(42);
+        assert_eq!(weighted(&mut rng, [0, 0].into_iter()), None);
+        assert_eq!(weighted(&mut rng, std::iter::empty()), None);
+        for _ in 0..100 {
+            assert_eq!(weighted(&mut rng, [0, 5, 0].into_iter()), Some(1));
+        }
+        let mut counts = [0; 2];
+        for _ in 0..1000 {
+            counts[weighted(&mut rng, [1, 3].into_iter()).unwrap()] += 1;
+        }
+        assert!(counts[0] > 150 && counts[0] < 350, "{:?}", counts);
+    }
+
+    #[test]
+    fn chance_bounds() {
+        let mut rng = StdRng::seed_from_u64(42);
+        assert!((0..100).all(|_| chance(&mut rng, 1.0)));
+        assert!((0..100).all(|_| !chance(&mut rng, 0.0)));
+    }
+}
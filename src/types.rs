@@ -5,10 +5,13 @@ use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
 use bevy::platform::collections::HashMap;
 use bevy::reflect::utility::GenericTypeInfoCell;
+use bevy::ecs::lifecycle::HookContext;
+use bevy::ecs::world::DeferredWorld;
 
-use bevy::reflect::{ApplyError, GetTypeRegistration, ReflectMut, ReflectOwned, ReflectRef, OpaqueInfo, TypeInfo, TypePath, Typed};
+use bevy::reflect::{ApplyError, GetTypeRegistration, ReflectMut, ReflectOwned, ReflectRef, OpaqueInfo, TypeInfo, TypePath, TypeRegistry, Typed};
 
-use crate::jobs::JobPaused;
+use crate::calendar::CalendarSchedule;
+use crate::jobs::{JobPaused, StartJobEvent, FailJobEvent, JobStarted, JobTaskChanged, JobCompleted, JobFailed, JobCancelled, JobCancelReason, JobEnded, JobOutcome};
 
 // #[typetag::serde(tag = "type")]
 pub trait PGTask: Reflect + Any + Send + Sync + DynClone + Debug {
@@ -21,9 +24,10 @@ dyn_clone::clone_trait_object!(PGTask);
 
 #[derive(Debug, Reflect, Resource, Clone, Component)]
 pub struct Task {
-    pub id:     u32,
-    pub next:   Option<u32>,
-    pub task:   Box<dyn PGTask + 'static>
+    pub id:       u32,
+    pub next:     Option<u32>,
+    pub on_fail:  Option<JobOnFail>,   // Overrides JobData::on_fail for this task
+    pub task:     Box<dyn PGTask + 'static>
 }
 
 #[derive(Debug, Reflect, Clone)]
@@ -70,6 +74,11 @@ impl JobTasks {
         self.data.get_mut(&self.last_added).unwrap().next = Some(next);
     }
 
+    pub fn with_on_fail(&mut self, on_fail: JobOnFail) -> &mut Self {
+        self.data.get_mut(&self.last_added).unwrap().on_fail = Some(on_fail);
+        return self;
+    }
+
     pub fn first(
         &mut self, 
         task: Box<dyn PGTask>
@@ -77,6 +86,7 @@ impl JobTasks {
         let t: Task = Task{
             id: 0, 
             next: None,
+            on_fail: None,
             task
         };
         self.data.insert(0, t);
@@ -92,6 +102,7 @@ impl JobTasks {
         let t: Task = Task{
             id, 
             next: None,
+            on_fail: None,
             task
         };
         self.data.insert(id, t);
@@ -107,6 +118,7 @@ impl JobTasks {
         let t: Task = Task{
             id, 
             next: None,
+            on_fail: None,
             task
         };
         self.data.insert(id, t);
@@ -156,39 +168,304 @@ impl JobTasks {
     }
 }
 
-#[derive(Clone, Copy, Default, Reflect, Debug)]
+/// Value that can be passed to a job as a parameter
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum JobValue {
+    Entity(Entity),
+    Vec3(Vec3),
+    Float(f32),
+    Int(i64),
+    Bool(bool),
+    Text(String)
+}
+impl JobValue {
+    pub fn as_entity(&self) -> Option<Entity> {
+        match self {JobValue::Entity(e) => Some(*e), _ => None}
+    }
+    pub fn as_vec3(&self) -> Option<Vec3> {
+        match self {JobValue::Vec3(v) => Some(*v), _ => None}
+    }
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            JobValue::Float(v) => Some(*v), 
+            JobValue::Int(v) => Some(*v as f32), 
+            _ => None
+        }
+    }
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {JobValue::Int(v) => Some(*v), _ => None}
+    }
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {JobValue::Bool(v) => Some(*v), _ => None}
+    }
+    pub fn as_str(&self) -> Option<&str> {
+        match self {JobValue::Text(v) => Some(v.as_str()), _ => None}
+    }
+}
+impl From<Entity> for JobValue {
+    fn from(v: Entity) -> Self {JobValue::Entity(v)}
+}
+impl From<Vec3> for JobValue {
+    fn from(v: Vec3) -> Self {JobValue::Vec3(v)}
+}
+impl From<f32> for JobValue {
+    fn from(v: f32) -> Self {JobValue::Float(v)}
+}
+impl From<i64> for JobValue {
+    fn from(v: i64) -> Self {JobValue::Int(v)}
+}
+impl From<bool> for JobValue {
+    fn from(v: bool) -> Self {JobValue::Bool(v)}
+}
+impl From<String> for JobValue {
+    fn from(v: String) -> Self {JobValue::Text(v)}
+}
+impl From<&str> for JobValue {
+    fn from(v: &str) -> Self {JobValue::Text(v.to_string())}
+}
+
+/// Named parameters given to a job when it starts, readable by its tasks
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+pub struct JobParams {
+    data:   HashMap<String, JobValue>,
+    parent: Option<Entity>   // Entity whose job started this one, receives escalated failures
+}
+impl JobParams {
+    pub fn new() -> Self {
+        JobParams::default()
+    }
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+    pub fn parent(&self) -> Option<Entity> {
+        self.parent
+    }
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<JobValue>) -> Self {
+        self.set(key, value);
+        self
+    }
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<JobValue>) {
+        self.data.insert(key.into(), value.into());
+    }
+    pub fn get(&self, key: &str) -> Option<&JobValue> {
+        self.data.get(key)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &JobValue)> {
+        self.data.iter()
+    }
+}
+
+/// Key/value memory of a job, tasks can write values for later tasks to read
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+pub struct JobBlackboard {
+    data: HashMap<String, JobValue>
+}
+impl JobBlackboard {
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<JobValue>) {
+        self.data.insert(key.into(), value.into());
+    }
+    pub fn get(&self, key: &str) -> Option<&JobValue> {
+        self.data.get(key)
+    }
+    pub fn remove(&mut self, key: &str) -> Option<JobValue> {
+        self.data.remove(key)
+    }
+    pub fn contains(&self, key: &str) -> bool {
+        self.data.contains_key(key)
+    }
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &JobValue)> {
+        self.data.iter()
+    }
+}
+
+/// Types that can be read from JobValue
+pub trait FromJobValue: Sized {
+    fn from_job_value(value: &JobValue) -> Option<Self>;
+}
+impl FromJobValue for Entity {
+    fn from_job_value(value: &JobValue) -> Option<Self> {value.as_entity()}
+}
+impl FromJobValue for Vec3 {
+    fn from_job_value(value: &JobValue) -> Option<Self> {value.as_vec3()}
+}
+impl FromJobValue for f32 {
+    fn from_job_value(value: &JobValue) -> Option<Self> {value.as_f32()}
+}
+impl FromJobValue for i64 {
+    fn from_job_value(value: &JobValue) -> Option<Self> {value.as_i64()}
+}
+impl FromJobValue for bool {
+    fn from_job_value(value: &JobValue) -> Option<Self> {value.as_bool()}
+}
+impl FromJobValue for String {
+    fn from_job_value(value: &JobValue) -> Option<Self> {value.as_str().map(|v| v.to_string())}
+}
+
+/// Task field that is either given directly or read from the job blackboard/parameters by key
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum JobArg<T> {
+    Value(T),
+    Key(String)
+}
+impl<T: FromJobValue + Clone> JobArg<T> {
+    pub fn key(key: impl Into<String>) -> Self {
+        JobArg::Key(key.into())
+    }
+    pub fn resolve(&self, job: &Job) -> Option<T> {
+        match self {
+            JobArg::Value(v) => Some(v.clone()),
+            JobArg::Key(key) => job.read(key).and_then(T::from_job_value)
+        }
+    }
+}
+impl<T> From<T> for JobArg<T> {
+    fn from(v: T) -> Self {
+        JobArg::Value(v)
+    }
+}
+
+/// Any reflected value kept in a task, e.g. a component to insert.
+/// The type has to be registered in the app type registry to be used.
+#[derive(Reflect)]
+#[reflect(opaque, Clone, Debug)]
+pub struct ReflectedValue(pub Box<dyn PartialReflect>);
+impl ReflectedValue {
+    pub fn new(value: impl Reflect) -> Self {
+        ReflectedValue(Box::new(value))
+    }
+    /// Owned copy of the value, concrete if the type supports reflect_clone
+    pub fn to_boxed(&self) -> Box<dyn PartialReflect> {
+        match self.0.reflect_clone() {
+            Ok(value) => value.into_partial_reflect(),
+            Err(_) => self.0.to_dynamic()
+        }
+    }
+    /// Type path of the value, None for dynamic values without represented type
+    pub fn type_path(&self) -> Option<&'static str> {
+        self.0.get_represented_type_info().map(|info| info.type_path())
+    }
+    /// Checks if the type is registered with #[reflect(Component)], insert_reflect panics otherwise
+    pub fn is_component(&self, registry: &TypeRegistry) -> bool {
+        self.type_path()
+            .and_then(|type_path| registry.get_with_type_path(type_path))
+            .is_some_and(|registration| registration.data::<ReflectComponent>().is_some())
+    }
+}
+impl Clone for ReflectedValue {
+    fn clone(&self) -> Self {
+        ReflectedValue(self.to_boxed())
+    }
+}
+impl Debug for ReflectedValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.debug(f)
+    }
+}
+
+#[derive(Clone, Copy, Default, Reflect, Debug, PartialEq)]
 pub enum JobOnFail {
     #[default]
     Cancel,
     RunTask(u32),
     Nothing,
-    Despawn
+    Despawn,                 // Despawn the entity right away, cleanup tasks are not run
+    Restart,                 // Start again from task 0 with counters and blackboard reset
+    RunJob(&'static str),    // Cancel with cleanup, then replace with recovery job from JobCatalog
+    Retry(u32),              // Retry failing task up to n times, then cancel
+    Escalate                 // Cancel and fail the parent job
+}
+
+/// Maximum lifetime of a job. Time is not counted while the job is paused.
+#[derive(Clone, Debug, Reflect, PartialEq)]
+pub enum JobLifetime {
+    RealSecs(f32),                 // Real time seconds
+    Minutes(u32),                  // In-game minutes
+    Hours(u32),                    // In-game hours
+    Days(u32),                     // In-game days
+    Until(CalendarSchedule)        // Until in-game time matches, e.g. closing time
+}
+
+#[derive(Clone, Copy, Debug, Default, Reflect, PartialEq)]
+pub enum JobExpireAction {
+    #[default]
+    Cancel,
+    Fail        // Applies JobOnFail strategy
+}
+
+#[derive(Clone, Debug, Reflect, PartialEq)]
+pub struct JobExpiry {
+    pub lifetime:  JobLifetime,
+    pub action:    JobExpireAction
+}
+impl JobExpiry {
+    pub fn new(lifetime: JobLifetime) -> Self {
+        JobExpiry { lifetime, action: JobExpireAction::Cancel }
+    }
+    pub fn fail(mut self) -> Self {
+        self.action = JobExpireAction::Fail;
+        self
+    }
+    pub fn parse(&mut self) {
+        if let JobLifetime::Until(schedule) = &mut self.lifetime {
+            schedule.parse();
+        }
+    }
 }
 
 /// JobData is read from job.toml files
-#[derive(Asset, Debug, Reflect, Clone)]
+#[derive(Asset, Debug, Reflect, Clone, Default)]
 pub struct JobData {
     /// Ideally unique name
     pub name:          &'static str,
     pub on_fail:       JobOnFail,
-    pub tasks:         JobTasks
+    pub tasks:         JobTasks,
+    /// Tasks run when the job is cancelled or fails, before the Job is removed
+    pub cleanup:       JobTasks,
+    /// Keep the Job on the entity after it ends, with Done or Inactive status, until it is cleared
+    pub retain:        bool,
+    /// Cancel or fail the job after its lifetime
+    pub expiry:        Option<JobExpiry>
 }
 
 impl JobData {
+    /// Parses Cron schedules used by the job, done when Job is created or data is added to JobCatalog
+    pub fn parse(&mut self) {
+        if let Some(expiry) = &mut self.expiry {
+            expiry.parse();
+        }
+    }
+
     pub fn assign(
         &self, 
         commands:  &mut Commands, 
         entity:    Entity
+    ) {
+        self.assign_with(commands, entity, JobParams::default());
+    }
+
+    pub fn assign_with(
+        &self, 
+        commands:  &mut Commands, 
+        entity:    Entity,
+        params:    JobParams
     ) {
         #[cfg(feature="verbose")]
         info!(" [JOBS] Assign JobData {} to {}", self.name, entity);
 
-        let mut job = Job::new(self.clone());
+        let mut job = Job::new(self.clone()).with_params(params);
         job.set_active();
         commands.entity(entity).insert(job);
 
         if let Some(first_task) = self.tasks.get_current(){
             first_task.task.insert(commands, &entity);
+            commands.write_message(JobStarted{entity, name: self.name, task_id: first_task.id});
         } else {
             #[cfg(feature="verbose")]
             warn!("Could not assign task to {}", entity);
@@ -198,14 +475,23 @@ impl JobData {
     pub fn start(
         &self, 
         commands: &mut Commands
+    ) -> Option<Entity>{ 
+        self.start_with(commands, JobParams::default())
+    }
+
+    pub fn start_with(
+        &self, 
+        commands: &mut Commands,
+        params:   JobParams
     ) -> Option<Entity>{ 
         #[cfg(feature="verbose")]
         info!(" [JOBS] Starting JobData {}", self.name);
         if let Some(first_task) = self.tasks.get_current(){
             let job_entity = first_task.task.spawn(commands);
-            let mut job = Job::new(self.clone());
+            let mut job = Job::new(self.clone()).with_params(params);
             job.set_active();
             commands.entity(job_entity).insert(job);
+            commands.write_message(JobStarted{entity: job_entity, name: self.name, task_id: first_task.id});
             return Some(job_entity);
         } else {
             return None;
@@ -219,31 +505,100 @@ impl JobData {
 pub enum JobStatus {
     ToDo,
     Active,
-    Done,
+    Done,       // Finished all tasks, only kept if JobData::retain
     Paused,
-    Inactive
+    Inactive    // Cancelled or failed, only kept if JobData::retain
 }
 
 #[derive(Component, Debug, Reflect, Clone)]
 #[reflect(Component)]
+#[component(on_replace = remove_job_task)]
 pub struct Job {
     loopk:             u32,              // Used for loops to count iterations
     status:            JobStatus,
+    finished_at:       Option<f64>,      // Real time when retained job ended
+    params:            JobParams,        // Parameters given when job was started
+    blackboard:        JobBlackboard,    // Values shared between tasks
+    cleaning:          bool,             // Running cleanup tasks after cancel or failure
+    retries:           u32,              // Retries of the current task
+    age:               f32,              // Real seconds the job was running, without pauses
+    game_age:          u32,              // In-game minutes the job was running, without pauses
+    cancel_reason:     Option<JobCancelReason>, // Why the job was cancelled, reported in JobEnded
+    recovery:          Option<&'static str>,    // Job started by JobOnFail::RunJob once this one ended
     pub data:          JobData,          // List of tasks to be performed by entity
 }
 
 
 impl Job {
     pub fn new(
-        data:       JobData
+        mut data:   JobData
     ) -> Self {
+        data.parse();
         Job {
             data,
             loopk: 0,
             status: JobStatus::ToDo,
+            finished_at: None,
+            params: JobParams::default(),
+            blackboard: JobBlackboard::default(),
+            cleaning: false,
+            retries: 0,
+            age: 0.0,
+            game_age: 0,
+            cancel_reason: None,
+            recovery: None
         }
     }
 
+    pub fn with_params(mut self, params: JobParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn params(&self) -> &JobParams {
+        &self.params
+    }
+
+    pub fn param(&self, key: &str) -> Option<&JobValue> {
+        self.params.get(key)
+    }
+
+    pub fn parent(&self) -> Option<Entity> {
+        self.params.parent()
+    }
+
+    /// Real seconds the job was running, without pauses
+    pub fn age(&self) -> f32 {
+        self.age
+    }
+
+    /// In-game minutes the job was running, without pauses
+    pub fn game_age(&self) -> u32 {
+        self.game_age
+    }
+
+    pub(crate) fn add_age(&mut self, secs: f32, minutes: u32) {
+        self.age += secs;
+        self.game_age += minutes;
+    }
+
+    pub fn blackboard(&self) -> &JobBlackboard {
+        &self.blackboard
+    }
+
+    pub fn blackboard_mut(&mut self) -> &mut JobBlackboard {
+        &mut self.blackboard
+    }
+
+    /// Reads value from the blackboard, falls back to job parameters
+    pub fn read(&self, key: &str) -> Option<&JobValue> {
+        self.blackboard.get(key).or_else(|| self.params.get(key))
+    }
+
+    pub fn write(&mut self, key: impl Into<String>, value: impl Into<JobValue>) {
+        self.blackboard.set(key, value);
+    }
+
     pub fn assign(
         &mut self, 
         commands:  &mut Commands, 
@@ -256,6 +611,7 @@ impl Job {
         commands.entity(entity).insert(self.clone());
         if let Some(first_task) = self.data.tasks.get_current(){
             first_task.task.insert(commands, &entity);
+            commands.write_message(JobStarted{entity, name: self.data.name, task_id: first_task.id});
         } else {
             #[cfg(feature="verbose")]
             warn!("Could not assign first task to entity: {}", entity);
@@ -272,6 +628,7 @@ impl Job {
         if let Some(first_task) = self.data.tasks.get_current(){
             let job_entity = first_task.task.spawn(commands);
             commands.entity(job_entity).insert(self.clone());
+            commands.write_message(JobStarted{entity: job_entity, name: self.data.name, task_id: first_task.id});
             return Some(job_entity);
         } else {
             error!(" [JOBS] Could not start job {}", self.data.name);
@@ -302,31 +659,138 @@ impl Job {
         commands:    &mut Commands, 
         task_entity: &Entity
     ) {
-        match self.data.on_fail {
+        self.fail_with(commands, task_entity, "");
+    }
+
+    /// Fails the job with a reason passed along in JobFailed message
+    pub fn fail_with(
+        &mut self, 
+        commands:    &mut Commands, 
+        task_entity: &Entity,
+        reason:      impl Into<String>
+    ) {
+        commands.write_message(JobFailed{
+            entity: *task_entity, 
+            name: self.data.name, 
+            task_id: self.data.tasks.current_task_id,
+            reason: reason.into()
+        });
+        if self.cleaning {
+            // Failure in cleanup tasks ends the job
+            self.remove_current(commands, task_entity);
+            self.end(commands, task_entity, JobStatus::Inactive);
+            return;
+        }
+        let on_fail = self.current_task().and_then(|task| task.on_fail).unwrap_or(self.data.on_fail);
+        match on_fail {
             JobOnFail::Nothing => {}
             JobOnFail::Despawn => {
+                self.cancel_reason = Some(JobCancelReason::Failed);
+                self.write_ended(commands, task_entity);
                 commands.entity(*task_entity).despawn();
             }
             JobOnFail::Cancel => {
-                self.cancel(commands, task_entity);
+                self.cancel_with(commands, task_entity, JobCancelReason::Failed);
             }
             JobOnFail::RunTask(task_id) => {
-                if let Some(next_task) = self.data.tasks.set_task(task_id){
-                    next_task.task.insert(commands, task_entity);
+                self.jump_task(commands, task_entity, task_id);
+                if self.current_task().is_none(){
+                    self.cancel_with(commands, task_entity, JobCancelReason::Failed);
+                }
+            }
+            JobOnFail::Restart => {
+                self.restart(commands, task_entity);
+            }
+            JobOnFail::RunJob(job_name) => {
+                // Started when the job ends, after its cleanup tasks
+                self.recovery = Some(job_name);
+                self.cancel_with(commands, task_entity, JobCancelReason::Failed);
+            }
+            JobOnFail::Retry(n) => {
+                if self.retries < n {
+                    self.retries += 1;
+                    self.remove_current(commands, task_entity);
+                    if let Some(task) = self.current_task(){
+                        task.task.insert(commands, task_entity);
+                    }
                 } else {
-                    self.cancel(commands, task_entity);
+                    self.cancel_with(commands, task_entity, JobCancelReason::Failed);
+                }
+            }
+            JobOnFail::Escalate => {
+                let parent = self.params.parent();
+                let reason = format!("Job {} failed on {}", self.data.name, task_entity);
+                self.cancel_with(commands, task_entity, JobCancelReason::Failed);
+                if let Some(parent) = parent {
+                    commands.write_message(FailJobEvent{entity: parent, reason});
                 }
             }
         }
     }
 
+    /// Starts the job again from task 0
+    pub fn restart(
+        &mut self, 
+        commands:    &mut Commands, 
+        task_entity: &Entity
+    ) {
+        self.jump_task(commands, task_entity, 0);
+        self.loopk = 0;
+        self.blackboard.clear();
+        if self.current_task().is_none(){
+            self.cancel_with(commands, task_entity, JobCancelReason::Failed);
+        }
+    }
+
     pub fn cancel(
         &mut self, 
         commands:    &mut Commands, 
         task_entity: &Entity
     ) {
+        self.cancel_with(commands, task_entity, JobCancelReason::Requested);
+    }
+
+    pub fn cancel_with(
+        &mut self, 
+        commands:    &mut Commands, 
+        task_entity: &Entity,
+        reason:      JobCancelReason
+    ) {
+        if !self.cleaning {
+            self.cancel_reason = Some(reason);
+            commands.write_message(JobCancelled{
+                entity: *task_entity, 
+                name: self.data.name, 
+                task_id: self.data.tasks.current_task_id,
+                reason
+            });
+        }
         self.remove_current(commands, task_entity);
-        commands.entity(*task_entity).remove::<Job>();
+        if !self.cleaning && !self.data.cleanup.data.is_empty(){
+            self.start_cleanup(commands, task_entity);
+        } else {
+            self.end(commands, task_entity, JobStatus::Inactive);
+        }
+    }
+
+    // Swaps job tasks for the cleanup tasks and inserts the first one
+    fn start_cleanup(
+        &mut self, 
+        commands:    &mut Commands, 
+        task_entity: &Entity
+    ) {
+        self.cleaning = true;
+        self.data.tasks = self.data.cleanup.clone();
+        if let Some(first_task) = self.data.tasks.get_current(){
+            first_task.task.insert(commands, task_entity);
+        } else {
+            self.end(commands, task_entity, JobStatus::Inactive);
+        }
+    }
+
+    /// Job is running its cleanup tasks after being cancelled or failed
+    pub fn is_cleaning(&self) -> bool {
+        self.cleaning
     }
 
     pub fn next_task(
@@ -335,13 +799,62 @@ impl Job {
         task_entity: &Entity
     ) {
         self.remove_current(commands, task_entity);
+        let from = self.data.tasks.current_task_id;
+        self.retries = 0;
         if let Some(next_task) = self.data.tasks.next_task(){
             next_task.task.insert(commands, task_entity);
+            commands.write_message(JobTaskChanged{entity: *task_entity, name: self.data.name, from, to: next_task.id});
+        } else if self.cleaning {
+            self.end(commands, task_entity, JobStatus::Inactive);
+        } else {
+            commands.write_message(JobCompleted{entity: *task_entity, name: self.data.name, task_id: from});
+            self.end(commands, task_entity, JobStatus::Done);
+        }
+    }
+
+    // Removes the job or keeps it with final status if retained
+    fn end(
+        &mut self, 
+        commands:    &mut Commands, 
+        task_entity: &Entity,
+        status:      JobStatus
+    ) {
+        self.write_ended(commands, task_entity);
+        if let Some(job_name) = self.recovery.take() {
+            commands.write_message(StartJobEvent::new(job_name, *task_entity).with_params(self.params.clone()));
+        }
+        if self.data.retain {
+            self.status = status;
+            self.finished_at = None;
         } else {
             commands.entity(*task_entity).remove::<Job>();
         }
     }
 
+    fn write_ended(
+        &self, 
+        commands:    &mut Commands, 
+        task_entity: &Entity
+    ) {
+        let outcome = match self.cancel_reason {
+            None => JobOutcome::Completed,
+            Some(JobCancelReason::Failed) => JobOutcome::Failed,
+            Some(_) => JobOutcome::Cancelled
+        };
+        commands.write_message(JobEnded{entity: *task_entity, name: self.data.name, outcome});
+    }
+
+    /// Removes retained finished job from the entity
+    pub fn clear(
+        &self, 
+        commands:    &mut Commands, 
+        task_entity: &Entity
+    ) {
+        if self.is_finished(){
+            commands.entity(*task_entity).remove::<Job>();
+        }
+    }
+
     pub fn jump_task(
         &mut self, 
         commands:    &mut Commands, 
@@ -349,8 +862,11 @@ impl Job {
         next_task_id: u32
     ) {
         self.remove_current(commands, task_entity);
+        let from = self.data.tasks.current_task_id;
+        self.retries = 0;
         if let Some(next_task) = self.data.tasks.set_task(next_task_id){
             next_task.task.insert(commands, task_entity);
+            commands.write_message(JobTaskChanged{entity: *task_entity, name: self.data.name, from, to: next_task_id});
         }
     }
 
@@ -370,6 +886,23 @@ impl Job {
         self.status
     }
 
+    pub fn status(&self) -> JobStatus {
+        self.status
+    }
+
+    /// Job ended and is only kept on the entity because of JobData::retain
+    pub fn is_finished(&self) -> bool {
+        self.status == JobStatus::Done || self.status == JobStatus::Inactive
+    }
+
+    pub fn finished_at(&self) -> Option<f64> {
+        self.finished_at
+    }
+
+    pub(crate) fn set_finished_at(&mut self, t: f64){
+        self.finished_at = Some(t);
+    }
+
     pub fn set_active(&mut self){
         self.status = JobStatus::Active;
     }
@@ -404,6 +937,15 @@ impl Job {
 
 }
 
+// Removes the current task component whenever Job is removed or replaced, so task systems stop running
+fn remove_job_task(mut world: DeferredWorld, ctx: HookContext) {
+    let Some(job) = world.get::<Job>(ctx.entity) else {return};
+    let Some(current) = job.current_task() else {return};
+    let task = current.task.clone();
+    let mut commands = world.commands();
+    task.remove(&mut commands, &ctx.entity);
+}
+
 impl GetTypeRegistration for Box<dyn PGTask> {
     fn get_type_registration() -> bevy::reflect::TypeRegistration {
         bevy::reflect::TypeRegistration::of::<Box<dyn PGTask>>()
@@ -542,3 +1084,186 @@ impl FromReflect for Box<dyn PGTask> {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::message::Messages;
+    use bevy::ecs::system::RunSystemOnce;
+    use pg_jobs_proc_macros::PGTask;
+
+    #[derive(Component, Clone, Debug, Reflect, PGTask)]
+    #[component(storage = "SparseSet")]
+    struct FirstTask;
+
+    #[derive(Component, Clone, Debug, Reflect, PGTask)]
+    #[component(storage = "SparseSet")]
+    struct SecondTask;
+
+    #[derive(Component, Clone, Debug, Reflect, PGTask)]
+    #[component(storage = "SparseSet")]
+    struct CleanupTask;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Messages<JobStarted>>();
+        world.init_resource::<Messages<JobTaskChanged>>();
+        world.init_resource::<Messages<JobCompleted>>();
+        world.init_resource::<Messages<JobFailed>>();
+        world.init_resource::<Messages<JobCancelled>>();
+        world.init_resource::<Messages<JobEnded>>();
+        world.init_resource::<Messages<StartJobEvent>>();
+        world.init_resource::<Messages<FailJobEvent>>();
+        world
+    }
+
+    // Two task job, FirstTask then SecondTask
+    fn jobdata(on_fail: JobOnFail) -> JobData {
+        let mut tasks = JobTasks::new();
+        tasks.first(Box::new(FirstTask));
+        tasks.next(Box::new(SecondTask));
+        JobData{name: "Test", tasks, on_fail, ..Default::default()}
+    }
+
+    fn with_cleanup(mut data: JobData) -> JobData {
+        data.cleanup.first(Box::new(CleanupTask));
+        data
+    }
+
+    fn assign(world: &mut World, data: JobData, params: JobParams) -> Entity {
+        let entity = world.spawn_empty().id();
+        world.run_system_once(move |mut commands: Commands| {
+            data.assign_with(&mut commands, entity, params.clone());
+        }).unwrap();
+        entity
+    }
+
+    fn fail(world: &mut World, entity: Entity) {
+        world.run_system_once(move |mut commands: Commands, mut jobs: Query<&mut Job>| {
+            jobs.get_mut(entity).unwrap().fail(&mut commands, &entity);
+        }).unwrap();
+    }
+
+    fn next(world: &mut World, entity: Entity) {
+        world.run_system_once(move |mut commands: Commands, mut jobs: Query<&mut Job>| {
+            jobs.get_mut(entity).unwrap().next_task(&mut commands, &entity);
+        }).unwrap();
+    }
+
+    fn messages<M: Message>(world: &World) -> Vec<&M> {
+        world.resource::<Messages<M>>().iter_current_update_messages().collect()
+    }
+
+    fn ended(world: &World) -> Vec<JobOutcome> {
+        messages::<JobEnded>(world).iter().map(|ev| ev.outcome).collect()
+    }
+
+    #[test]
+    fn retry_reinserts_task_then_cancels() {
+        let mut world = world();
+        let entity = assign(&mut world, jobdata(JobOnFail::Retry(2)), JobParams::new());
+
+        fail(&mut world, entity);
+        fail(&mut world, entity);
+        assert!(world.entity(entity).contains::<FirstTask>());
+        assert_eq!(world.get::<Job>(entity).unwrap().retries, 2);
+        assert!(ended(&world).is_empty());
+
+        fail(&mut world, entity);
+        assert!(world.get::<Job>(entity).is_none());
+        assert!(!world.entity(entity).contains::<FirstTask>());
+        assert_eq!(ended(&world), vec![JobOutcome::Failed]);
+    }
+
+    #[test]
+    fn restart_goes_back_to_first_task() {
+        let mut world = world();
+        let entity = assign(&mut world, jobdata(JobOnFail::Restart), JobParams::new());
+        next(&mut world, entity);
+        assert!(world.entity(entity).contains::<SecondTask>());
+
+        fail(&mut world, entity);
+        assert!(world.entity(entity).contains::<FirstTask>());
+        assert!(!world.entity(entity).contains::<SecondTask>());
+        assert_eq!(world.get::<Job>(entity).unwrap().current_task().unwrap().id, 0);
+        assert!(ended(&world).is_empty());
+    }
+
+    #[test]
+    fn run_job_starts_recovery_after_cleanup() {
+        let mut world = world();
+        let params = JobParams::new().with("target", 3);
+        let entity = assign(&mut world, with_cleanup(jobdata(JobOnFail::RunJob("Recovery"))), params);
+
+        fail(&mut world, entity);
+        assert!(world.entity(entity).contains::<CleanupTask>());
+        assert!(!world.entity(entity).contains::<FirstTask>());
+        assert!(world.get::<Job>(entity).unwrap().is_cleaning());
+        assert!(messages::<StartJobEvent>(&world).is_empty());
+
+        next(&mut world, entity);
+        assert!(world.get::<Job>(entity).is_none());
+        assert!(!world.entity(entity).contains::<CleanupTask>());
+        assert_eq!(ended(&world), vec![JobOutcome::Failed]);
+        let started = messages::<StartJobEvent>(&world);
+        assert_eq!(started.len(), 1);
+        assert_eq!(started[0].name, "Recovery");
+        assert_eq!(started[0].entity, entity);
+        assert_eq!(started[0].params.get("target"), Some(&JobValue::Int(3)));
+    }
+
+    #[test]
+    fn run_job_without_cleanup_starts_recovery_right_away() {
+        let mut world = world();
+        let entity = assign(&mut world, jobdata(JobOnFail::RunJob("Recovery")), JobParams::new());
+
+        fail(&mut world, entity);
+        assert!(world.get::<Job>(entity).is_none());
+        assert!(!world.entity(entity).contains::<FirstTask>());
+        assert_eq!(messages::<StartJobEvent>(&world).len(), 1);
+    }
+
+    #[test]
+    fn escalate_fails_parent() {
+        let mut world = world();
+        let parent = world.spawn_empty().id();
+        let entity = assign(&mut world, jobdata(JobOnFail::Escalate), JobParams::new().with_parent(parent));
+
+        fail(&mut world, entity);
+        assert!(world.get::<Job>(entity).is_none());
+        let escalated = messages::<FailJobEvent>(&world);
+        assert_eq!(escalated.len(), 1);
+        assert_eq!(escalated[0].entity, parent);
+        assert_eq!(ended(&world), vec![JobOutcome::Failed]);
+    }
+
+    #[test]
+    fn escalate_without_parent_only_cancels() {
+        let mut world = world();
+        let entity = assign(&mut world, jobdata(JobOnFail::Escalate), JobParams::new());
+
+        fail(&mut world, entity);
+        assert!(world.get::<Job>(entity).is_none());
+        assert!(messages::<FailJobEvent>(&world).is_empty());
+    }
+
+    #[test]
+    fn task_on_fail_overrides_job_policy() {
+        let mut world = world();
+        let mut data = jobdata(JobOnFail::Cancel);
+        data.tasks.with_on_fail(JobOnFail::Nothing);  // SecondTask was added last
+        let entity = assign(&mut world, data, JobParams::new());
+
+        // FirstTask uses the job policy
+        let other = assign(&mut world, jobdata(JobOnFail::Cancel), JobParams::new());
+        fail(&mut world, other);
+        assert!(world.get::<Job>(other).is_none());
+
+        // SecondTask overrides it with Nothing
+        next(&mut world, entity);
+        fail(&mut world, entity);
+        assert!(world.entity(entity).contains::<SecondTask>());
+        assert_eq!(world.get::<Job>(entity).unwrap().status(), JobStatus::Active);
+        assert_eq!(messages::<JobFailed>(&world).len(), 2);
+    }
+}